@@ -0,0 +1,69 @@
+use egui::{epaint::TextShape, Color32, Pos2, Shape, Stroke, TextStyle};
+
+use crate::draw::DrawContext;
+
+/// Radius of [`Annotation::Marker`] in screen pixels.
+const MARKER_RADIUS: f32 = 4.;
+
+/// Decoration drawn on top of the graph at graph coordinates.
+///
+/// Annotations follow zoom and pan of the graph but keep their screen size. They are purely
+/// decorative and do not participate in hit-testing.
+///
+/// ```
+/// use egui::{Color32, Pos2};
+/// use egui_graphs::Annotation;
+///
+/// let annotations = [
+///     Annotation::Text {
+///         pos: Pos2::new(0., -50.),
+///         text: "cluster A".to_string(),
+///     },
+///     Annotation::Marker {
+///         pos: Pos2::new(100., 100.),
+///         color: Color32::RED,
+///     },
+///     Annotation::Line {
+///         from: Pos2::new(0., 0.),
+///         to: Pos2::new(100., 100.),
+///     },
+/// ];
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Annotation {
+    /// Text centered at the position.
+    Text { pos: Pos2, text: String },
+    /// Small filled circle at the position.
+    Marker { pos: Pos2, color: Color32 },
+    /// Line segment between two positions.
+    Line { from: Pos2, to: Pos2 },
+}
+
+impl Annotation {
+    pub(crate) fn shape(&self, ctx: &DrawContext) -> Shape {
+        let style = ctx.ctx.style();
+        let text_color = style.visuals.text_color();
+
+        match self {
+            Annotation::Text { pos, text } => {
+                let font = TextStyle::Body.resolve(&style);
+                let galley = ctx
+                    .ctx
+                    .fonts(|f| f.layout_no_wrap(text.clone(), font, text_color));
+                let center = ctx.meta.canvas_to_screen_pos(*pos);
+                let text_pos = center - galley.size() / 2.;
+                TextShape::new(text_pos, galley, text_color).into()
+            }
+            Annotation::Marker { pos, color } => {
+                Shape::circle_filled(ctx.meta.canvas_to_screen_pos(*pos), MARKER_RADIUS, *color)
+            }
+            Annotation::Line { from, to } => Shape::line_segment(
+                [
+                    ctx.meta.canvas_to_screen_pos(*from),
+                    ctx.meta.canvas_to_screen_pos(*to),
+                ],
+                Stroke::new(1., text_color),
+            ),
+        }
+    }
+}
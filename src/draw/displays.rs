@@ -77,4 +77,19 @@ where
         end: &Node<N, E, Ty, Ix, D>,
         pos: Pos2,
     ) -> bool;
+
+    /// Checks if the provided `pos` is at most `tolerance` away from the shape, both in canvas
+    /// coordinates. Used to pick edges with the pointer, so that thin edges stay clickable.
+    ///
+    /// Defaults to [`DisplayEdge::is_inside`], ignoring the tolerance.
+    fn is_inside_with_tolerance(
+        &self,
+        start: &Node<N, E, Ty, Ix, D>,
+        end: &Node<N, E, Ty, Ix, D>,
+        pos: Pos2,
+        tolerance: f32,
+    ) -> bool {
+        let _ = tolerance;
+        self.is_inside(start, end, pos)
+    }
 }
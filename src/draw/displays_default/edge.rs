@@ -1,19 +1,58 @@
-use core::panic;
-
-use egui::{
-    epaint::{CubicBezierShape, TextShape},
-    Color32, FontFamily, FontId, Pos2, Shape, Stroke, Vec2,
-};
+use egui::{epaint::CubicBezierShape, Color32, FontFamily, FontId, Pos2, Shape, Stroke, Vec2};
 use petgraph::{stable_graph::IndexType, EdgeType};
 
-use crate::{draw::DrawContext, elements::EdgeProps, node_size, DisplayEdge, DisplayNode, Node};
+use crate::{
+    draw::DrawContext,
+    elements::{EdgeProps, EdgeStyle},
+    node_size,
+    settings::CurveSegments,
+    DisplayEdge, DisplayNode, Node,
+};
 
-use super::edge_shape_builder::{EdgeShapeBuilder, TipProps};
+use super::{
+    edge_shape_builder::{
+        curve_points, dash_segments, direction_ticks, gradient_segments, EdgeShapeBuilder, TipProps,
+    },
+    label::label_shapes,
+};
 
+/// Factor by which hovered edges are drawn wider.
+const HOVER_WIDTH_SCALE: f32 = 1.5;
+
+/// Width of edges without [`EdgeProps::width`].
+const DEFAULT_WIDTH: f32 = 2.;
+
+/// Number of colors sampled from [`EdgeProps::color_fn`] along the edge.
+const COLOR_SAMPLES: usize = 16;
+
+/// Length of dashes of [`EdgeStyle::Dashed`] edges in canvas units.
+const DASH_LENGTH: f32 = 10.;
+
+/// Length of gaps between dashes of [`EdgeStyle::Dashed`] edges in canvas units.
+const DASH_GAP: f32 = 6.;
+
+/// Length of gaps between dots of [`EdgeStyle::Dotted`] edges in canvas units.
+const DOT_GAP: f32 = 4.;
+
+/// This is the default edge shape which is used to display edges in the graph.
+///
+/// If the edge has a [`EdgeProps::color_fn`], its body is drawn as a path of
+/// differently colored parts, while the tip and the label use the color at the end of the
+/// edge. Otherwise the edge is drawn in [`EdgeProps::color`] if set, or in the edge color of the
+/// [`crate::SettingsStyle`]. Selected, hovered and highlighted edges are drawn in a uniform
+/// interaction color, which can be configured with [`crate::SettingsStyle::with_color_selected`]
+/// and [`crate::SettingsStyle::with_color_highlighted`]. Hovered edges are also drawn wider.
+/// The body is drawn solid, dashed or dotted depending on [`EdgeProps::style`].
+///
+/// Edges of directed graphs end with a tip touching the boundary of the target node, unless
+/// disabled with [`crate::SettingsStyle::with_edge_arrows`]. Loops are drawn above the node
+/// with a tip at their end pointing back to the node.
 #[derive(Clone, Debug)]
 pub struct DefaultEdgeShape {
     pub order: usize,
     pub selected: bool,
+    pub highlighted: bool,
+    pub hovered: bool,
 
     pub width: f32,
     pub tip_size: f32,
@@ -21,6 +60,10 @@ pub struct DefaultEdgeShape {
     pub curve_size: f32,
     pub loop_size: f32,
     pub label_text: String,
+    pub color: Option<Color32>,
+    pub style: EdgeStyle,
+    /// Colors sampled along the edge from source to target. Uniform color is used if empty.
+    pub colors: Vec<Color32>,
 }
 
 impl<E: Clone> From<EdgeProps<E>> for DefaultEdgeShape {
@@ -28,9 +71,14 @@ impl<E: Clone> From<EdgeProps<E>> for DefaultEdgeShape {
         Self {
             order: edge.order,
             selected: edge.selected,
+            highlighted: edge.highlighted,
+            hovered: edge.hovered,
+            colors: sample_colors(&edge),
             label_text: edge.label,
+            color: edge.color,
+            style: edge.style,
 
-            width: 2.,
+            width: edge.width.unwrap_or(DEFAULT_WIDTH),
             tip_size: 15.,
             tip_angle: std::f32::consts::TAU / 30.,
             curve_size: 20.,
@@ -48,18 +96,31 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
         end: &Node<N, E, Ty, Ix, D>,
         pos: egui::Pos2,
     ) -> bool {
+        self.is_inside_with_tolerance(start, end, pos, self.width / 2.)
+    }
+
+    /// The tolerance is measured from the outline of the edge, so wider edges are hit
+    /// further away from their center line.
+    fn is_inside_with_tolerance(
+        &self,
+        start: &Node<N, E, Ty, Ix, D>,
+        end: &Node<N, E, Ty, Ix, D>,
+        pos: Pos2,
+        tolerance: f32,
+    ) -> bool {
+        let max_distance = self.width / 2. + tolerance;
         if start.id() == end.id() {
-            return self.is_inside_loop(start, pos);
+            return self.is_inside_loop(start, pos, max_distance);
         }
 
         let pos_start = start.location();
         let pos_end = end.location();
 
         if self.order == 0 {
-            return self.is_inside_line(pos_start, pos_end, pos);
+            return distance_segment_to_point(pos_start, pos_end, pos) <= max_distance;
         }
 
-        self.is_inside_curve(start, end, pos)
+        self.is_inside_curve(start, end, pos, max_distance)
     }
 
     #[allow(clippy::too_many_lines)] // TODO: refactor
@@ -71,95 +132,107 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
     ) -> Vec<egui::Shape> {
         let mut res = vec![];
 
-        let label_visible = ctx.style.labels_always || self.selected;
-
-        let style = if self.selected {
-            ctx.ctx.style().visuals.widgets.active
+        let label_visible =
+            ctx.style.labels_always || self.selected || (self.hovered && ctx.style.labels_on_hover);
+
+        let egui_style = ctx.ctx.style();
+        let widgets = &egui_style.visuals.widgets;
+        let style_color = if self.selected {
+            ctx.style
+                .color_selected
+                .unwrap_or(widgets.active.fg_stroke.color)
+        } else if self.hovered {
+            widgets.hovered.fg_stroke.color
+        } else if self.highlighted {
+            ctx.style
+                .color_highlighted
+                .unwrap_or(widgets.hovered.fg_stroke.color)
         } else {
-            ctx.ctx.style().visuals.widgets.inactive
+            ctx.style
+                .color_edge
+                .unwrap_or(widgets.inactive.fg_stroke.color)
         };
-        let color = style.fg_stroke.color;
-        let stroke = Stroke::new(self.width, color);
+        let interacted = self.selected || self.hovered || self.highlighted;
+        let gradient = (!interacted && !self.colors.is_empty()).then_some(self.colors.as_slice());
+        let color = gradient
+            .and_then(|colors| colors.last().copied())
+            .or(self.color.filter(|_| !interacted))
+            .unwrap_or(style_color);
+        let width = if self.hovered {
+            self.width * HOVER_WIDTH_SCALE
+        } else {
+            self.width
+        };
+        let stroke = Stroke::new(width, color);
 
         if start.id() == end.id() {
             // draw loop
+
+            // the style override is stored in the shape so hit testing matches the drawn loop
+            if let Some(loop_size) = ctx.style.self_loop_size {
+                self.loop_size = loop_size;
+            }
+
             let size = node_size(start, Vec2::new(-1., 0.));
-            let mut line_looped_shapes = EdgeShapeBuilder::new(stroke)
+            let mut builder = EdgeShapeBuilder::new(stroke)
                 .looped(start.location(), size, self.loop_size, self.order)
-                .with_scaler(ctx.meta)
-                .build();
-            let line_looped_shape = line_looped_shapes.clone().pop().unwrap();
-            res.push(line_looped_shape);
-
-            let Shape::CubicBezier(line_looped) = line_looped_shapes.pop().unwrap() else {
+                .with_scaler(ctx.meta);
+            let tip_props = self.tip_props(ctx);
+            if let Some(tip_props) = &tip_props {
+                builder = builder.with_tip(tip_props);
+            }
+            let line_looped_shapes = builder.build();
+            let Some(Shape::CubicBezier(line_looped)) = line_looped_shapes.first().cloned() else {
                 panic!("invalid shape type")
             };
+            res.extend(body_shapes(line_looped_shapes, gradient, self.style, ctx));
 
-            // TODO: export to func
             if label_visible {
-                let galley = ctx.ctx.fonts(|f| {
-                    f.layout_no_wrap(
-                        self.label_text.clone(),
-                        FontId::new(ctx.meta.canvas_to_screen_size(size), FontFamily::Monospace),
-                        color,
-                    )
-                });
-
                 let flattened_curve = line_looped.flatten(None);
                 let median = *flattened_curve.get(flattened_curve.len() / 2).unwrap();
-
-                let label_width = galley.rect.width();
-                let label_height = galley.rect.height();
-                let pos = Pos2::new(median.x - label_width / 2., median.y - label_height);
-
-                let label_shape = TextShape::new(pos, galley, color);
-                res.push(label_shape.into());
+                res.extend(self.label(median, size, color, ctx));
             }
             return res;
         }
 
-        let dir = (end.location() - start.location()).normalized();
-        let start_connector_point = start.display().closest_boundary_point(dir);
-        let end_connector_point = end.display().closest_boundary_point(-dir);
+        let (start_connector_point, end_connector_point, dir) = connector_points(start, end);
 
         if self.order == 0 {
             // draw straight edge
 
-            let mut builder = EdgeShapeBuilder::new(stroke)
-                .straight((start_connector_point, end_connector_point))
-                .with_scaler(ctx.meta);
+            let tip_props = self.tip_props(ctx);
+            let tip_size = tip_props.as_ref().map(|tip| tip.size);
+            if let Some((bounds, with_body)) =
+                straight_bounds(start_connector_point, end_connector_point, dir, tip_size)
+            {
+                let mut builder = EdgeShapeBuilder::new(stroke)
+                    .straight(bounds)
+                    .with_scaler(ctx.meta);
+                if !with_body {
+                    builder = builder.without_body();
+                }
+                if let Some(tip_props) = &tip_props {
+                    builder = builder.with_tip(tip_props);
+                }
+                let straight_shapes = builder.build();
+                res.extend(body_shapes(straight_shapes, gradient, self.style, ctx));
+            }
 
-            let tip_props = TipProps {
-                size: self.tip_size,
-                angle: self.tip_angle,
-            };
-            if ctx.is_directed {
-                builder = builder.with_tip(&tip_props);
-            };
-            let straight_shapes = builder.build();
-            res.extend(straight_shapes);
+            if ctx.is_directed && ctx.style.edge_direction_ticks {
+                let points = [
+                    ctx.meta.canvas_to_screen_pos(start_connector_point),
+                    ctx.meta.canvas_to_screen_pos(end_connector_point),
+                ];
+                res.extend(self.direction_ticks(&points, stroke, ctx));
+            }
 
-            // TODO: export to func
             if label_visible {
                 let size = (node_size(start, dir) + node_size(end, dir)) / 2.;
-                let galley = ctx.ctx.fonts(|f| {
-                    f.layout_no_wrap(
-                        self.label_text.clone(),
-                        FontId::new(ctx.meta.canvas_to_screen_size(size), FontFamily::Monospace),
-                        color,
-                    )
-                });
-
                 let dist = end_connector_point - start_connector_point;
                 let center = ctx
                     .meta
                     .canvas_to_screen_pos(start_connector_point + dist / 2.);
-                let label_width = galley.rect.width();
-                let label_height = galley.rect.height();
-                let pos = Pos2::new(center.x - label_width / 2., center.y - label_height);
-
-                let label_shape = TextShape::new(pos, galley, color);
-                res.push(label_shape.into());
+                res.extend(self.label(center, size, color, ctx));
             }
 
             return res;
@@ -173,38 +246,32 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
             )
             .with_scaler(ctx.meta);
 
-        let tip_props = TipProps {
-            size: self.tip_size,
-            angle: self.tip_angle,
-        };
-        if ctx.is_directed {
-            builder = builder.with_tip(&tip_props);
+        let tip_props = self.tip_props(ctx);
+        if let Some(tip_props) = &tip_props {
+            builder = builder.with_tip(tip_props);
         };
         let curved_shapes = builder.build();
         let Some(Shape::CubicBezier(line_curved)) = curved_shapes.first() else {
             panic!("invalid shape type")
         };
-        res.extend(curved_shapes.clone());
+        res.extend(body_shapes(
+            curved_shapes.clone(),
+            gradient,
+            self.style,
+            ctx,
+        ));
+
+        if ctx.is_directed && ctx.style.edge_direction_ticks {
+            let points = curve_points(line_curved, ctx.style.curve_segments);
+            res.extend(self.direction_ticks(&points, stroke, ctx));
+        }
 
         if label_visible {
+            // the middle of the curve is its apex
             let size = (node_size(start, dir) + node_size(end, dir)) / 2.;
-            let galley = ctx.ctx.fonts(|f| {
-                f.layout_no_wrap(
-                    self.label_text.clone(),
-                    FontId::new(ctx.meta.canvas_to_screen_size(size), FontFamily::Monospace),
-                    color,
-                )
-            });
-
             let flattened_curve = line_curved.flatten(None);
             let median = *flattened_curve.get(flattened_curve.len() / 2).unwrap();
-
-            let label_width = galley.rect.width();
-            let label_height = galley.rect.height();
-            let pos = Pos2::new(median.x - label_width / 2., median.y - label_height);
-
-            let label_shape = TextShape::new(pos, galley, color);
-            res.push(label_shape.into());
+            res.extend(self.label(median, size, color, ctx));
         }
 
         res
@@ -213,11 +280,142 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode<N, E, Ty, I
     fn update(&mut self, state: &EdgeProps<E>) {
         self.order = state.order;
         self.selected = state.selected;
+        self.highlighted = state.highlighted;
+        self.hovered = state.hovered;
         self.label_text = state.label.to_string();
+        self.color = state.color;
+        self.style = state.style;
+        self.colors = sample_colors(state);
+        if let Some(width) = state.width {
+            self.width = width;
+        }
+    }
+}
+
+/// Samples the color function of the edge in the middle of each of [`COLOR_SAMPLES`] parts.
+fn sample_colors<E: Clone>(props: &EdgeProps<E>) -> Vec<Color32> {
+    let Some(color_fn) = props.color_fn else {
+        return vec![];
+    };
+
+    (0..COLOR_SAMPLES)
+        .map(|i| color_fn((i as f32 + 0.5) / COLOR_SAMPLES as f32, &props.payload))
+        .collect()
+}
+
+/// Replaces uniformly colored edge body in `shapes` with parts colored by `gradient`,
+/// flattens curves with the number of segments from the style settings and breaks the body
+/// into dashes or dots of the edge `style`.
+fn body_shapes(
+    shapes: Vec<Shape>,
+    gradient: Option<&[Color32]>,
+    style: EdgeStyle,
+    ctx: &DrawContext,
+) -> Vec<Shape> {
+    let segments = ctx.style.curve_segments;
+    let shapes = shapes
+        .into_iter()
+        .flat_map(|shape| match (shape, gradient) {
+            (Shape::LineSegment { points, stroke }, Some(colors)) => {
+                gradient_segments(&points, colors, stroke.width)
+            }
+            (Shape::CubicBezier(curve), Some(colors)) => {
+                gradient_segments(&curve_points(&curve, segments), colors, curve.stroke.width)
+            }
+            (Shape::CubicBezier(curve), None) if segments != CurveSegments::Auto => {
+                vec![Shape::line(curve_points(&curve, segments), curve.stroke)]
+            }
+            (shape, _) => vec![shape],
+        })
+        .collect();
+
+    styled_shapes(shapes, style, ctx)
+}
+
+/// Breaks the edge body in `shapes` into dashes or dots of the `style`, continuing the pattern
+/// across consecutive parts of the body. Closed shapes like tips stay solid.
+fn styled_shapes(shapes: Vec<Shape>, style: EdgeStyle, ctx: &DrawContext) -> Vec<Shape> {
+    if style == EdgeStyle::Solid {
+        return shapes;
+    }
+
+    let mut res = vec![];
+    let mut phase = 0.;
+    for shape in shapes {
+        let (points, stroke) = match shape {
+            Shape::LineSegment { points, stroke } => (points.to_vec(), stroke),
+            Shape::Path(path) if !path.closed => (path.points, path.stroke),
+            Shape::CubicBezier(curve) if !curve.closed => {
+                (curve_points(&curve, CurveSegments::Auto), curve.stroke)
+            }
+            shape => {
+                res.push(shape);
+                continue;
+            }
+        };
+
+        let (dash, gap) = match style {
+            EdgeStyle::Solid => unreachable!(),
+            EdgeStyle::Dashed => (
+                ctx.meta.canvas_to_screen_size(DASH_LENGTH),
+                ctx.meta.canvas_to_screen_size(DASH_GAP),
+            ),
+            EdgeStyle::Dotted => (stroke.width, ctx.meta.canvas_to_screen_size(DOT_GAP)),
+        };
+        let (dashes, next_phase) = dash_segments(&points, &stroke, dash, gap, phase);
+        res.extend(dashes);
+        phase = next_phase;
     }
+
+    res
 }
 
 impl DefaultEdgeShape {
+    /// Returns shapes of the label centered above `anchor` in screen coordinates with the font
+    /// `size` in canvas units, or nothing if the font would be smaller than
+    /// [`crate::SettingsStyle::with_edge_label_min_size`].
+    fn label(&self, anchor: Pos2, size: f32, color: Color32, ctx: &DrawContext) -> Vec<Shape> {
+        let font_size = ctx.meta.canvas_to_screen_size(size);
+        if self.label_text.is_empty()
+            || ctx
+                .style
+                .edge_label_min_size
+                .is_some_and(|min| font_size < min)
+        {
+            return vec![];
+        }
+
+        let color = ctx.style.edge_label_color.unwrap_or(color);
+        let galley = ctx.ctx.fonts(|f| {
+            f.layout_no_wrap(
+                self.label_text.clone(),
+                FontId::new(font_size, FontFamily::Monospace),
+                color,
+            )
+        });
+
+        let label_width = galley.rect.width();
+        let label_height = galley.rect.height();
+        let pos = Pos2::new(anchor.x - label_width / 2., anchor.y - label_height);
+
+        label_shapes(pos, galley, color, ctx)
+    }
+
+    /// Returns properties of the tip, or `None` if the edge is drawn without a tip.
+    fn tip_props(&self, ctx: &DrawContext) -> Option<TipProps> {
+        (ctx.is_directed && ctx.style.edge_arrows).then(|| TipProps {
+            size: ctx.style.edge_arrow_size.unwrap_or(self.tip_size),
+            angle: self.tip_angle,
+        })
+    }
+
+    /// Builds direction ticks along the edge path given in screen coordinates.
+    fn direction_ticks(&self, points: &[Pos2], stroke: Stroke, ctx: &DrawContext) -> Vec<Shape> {
+        let size = ctx.meta.canvas_to_screen_size(self.tip_size / 2.);
+        let stroke = Stroke::new(ctx.meta.canvas_to_screen_size(stroke.width), stroke.color);
+        direction_ticks(points, ctx.style.tick_spacing, size, stroke)
+    }
+
     fn is_inside_loop<
         E: Clone,
         N: Clone,
@@ -228,6 +426,7 @@ impl DefaultEdgeShape {
         &self,
         node: &Node<N, E, Ty, Ix, D>,
         pos: Pos2,
+        max_distance: f32,
     ) -> bool {
         let node_size = node_size(node, Vec2::new(-1., 0.));
 
@@ -236,16 +435,11 @@ impl DefaultEdgeShape {
             .build();
 
         match shape.first() {
-            Some(Shape::CubicBezier(cubic)) => is_point_on_curve(pos, cubic),
+            Some(Shape::CubicBezier(cubic)) => is_point_on_curve(pos, cubic, max_distance),
             _ => panic!("invalid shape type"),
         }
     }
 
-    fn is_inside_line(&self, pos_start: Pos2, pos_end: Pos2, pos: Pos2) -> bool {
-        let distance = distance_segment_to_point(pos_start, pos_end, pos);
-        distance <= self.width
-    }
-
     fn is_inside_curve<
         N: Clone,
         E: Clone,
@@ -257,6 +451,7 @@ impl DefaultEdgeShape {
         node_start: &Node<N, E, Ty, Ix, D>,
         node_end: &Node<N, E, Ty, Ix, D>,
         pos: Pos2,
+        max_distance: f32,
     ) -> bool {
         let dir = (node_end.location() - node_start.location()).normalized();
         let start = node_start.display().closest_boundary_point(dir);
@@ -270,7 +465,54 @@ impl DefaultEdgeShape {
             _ => panic!("invalid shape type"),
         };
 
-        is_point_on_curve(pos, &curved_shape)
+        is_point_on_curve(pos, &curved_shape, max_distance)
+    }
+}
+
+/// Returns the points where the edge leaves the `start` node and enters the `end` node, and the
+/// direction from the start node center to the end node center.
+///
+/// Edges are drawn between these points, so neither the body nor the tip overlaps the nodes.
+fn connector_points<N, E, Ty, Ix, D>(
+    start: &Node<N, E, Ty, Ix, D>,
+    end: &Node<N, E, Ty, Ix, D>,
+) -> (Pos2, Pos2, Vec2)
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    D: DisplayNode<N, E, Ty, Ix>,
+{
+    let dir = (end.location() - start.location()).normalized();
+    (
+        start.display().closest_boundary_point(dir),
+        end.display().closest_boundary_point(-dir),
+        dir,
+    )
+}
+
+/// Returns bounds of a straight edge between the boundary points of its nodes and whether
+/// the line body of the edge should be drawn.
+///
+/// * `dir` - direction from the start node center to the end node center.
+/// * `tip_size` - size of the tip for directed edges.
+///
+/// When the boundary points are closer than the tip size, or the nodes overlap so that the
+/// boundary points swap, there is no room for the line body. Then for directed edges only the tip
+/// is drawn touching the end node and pointing along `dir`, and undirected edges are not drawn
+/// at all (`None` is returned). This avoids inverted slivers when nodes are very close.
+fn straight_bounds(
+    start: Pos2,
+    end: Pos2,
+    dir: Vec2,
+    tip_size: Option<f32>,
+) -> Option<((Pos2, Pos2), bool)> {
+    let gap = (end - start).dot(dir);
+    match tip_size {
+        Some(size) if gap < size => Some(((end - dir * size, end), false)),
+        None if gap <= 0. => None,
+        _ => Some(((start, end), true)),
     }
 }
 
@@ -280,6 +522,11 @@ fn distance_segment_to_point(a: Pos2, b: Pos2, point: Pos2) -> f32 {
     let ac = point - a;
     let ab = b - a;
 
+    // degenerate segment, e.g. between overlapping nodes
+    if ab == Vec2::ZERO {
+        return hypot2(point.to_vec2(), a.to_vec2()).sqrt();
+    }
+
     let d = a + proj(ac, ab);
 
     let ad = d - a;
@@ -310,9 +557,9 @@ fn proj(a: Vec2, b: Vec2) -> Vec2 {
     Vec2::new(k * b.x, k * b.y)
 }
 
-fn is_point_on_curve(point: Pos2, curve: &CubicBezierShape) -> bool {
+fn is_point_on_curve(point: Pos2, curve: &CubicBezierShape, max_distance: f32) -> bool {
     for p in curve.flatten(None) {
-        if p.distance(point) < curve.stroke.width {
+        if p.distance(point) <= max_distance {
             return true;
         }
     }
@@ -321,7 +568,60 @@ fn is_point_on_curve(point: Pos2, curve: &CubicBezierShape) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use egui::{Context, LayerId, Painter, RawInput, Rect};
+    use petgraph::{stable_graph::DefaultIx, Directed};
+
     use super::*;
+    use crate::{Edge, Metadata, SettingsStyle};
+
+    #[test]
+    fn test_edge_width() {
+        let widths = [0.5, 2., 7.5];
+        for width in widths {
+            let e: Edge<(), ()> = Edge::new(()).with_width(width);
+            assert_eq!(e.width(), Some(width));
+            assert_eq!(DefaultEdgeShape::from(e.props().clone()).width, width);
+        }
+
+        let e: Edge<(), ()> = Edge::new(());
+        assert_eq!(e.width(), None);
+        assert_eq!(e.display().width, DEFAULT_WIDTH);
+    }
+
+    #[test]
+    fn test_label_min_size() {
+        let ctx = Context::default();
+        let _ = ctx.run(RawInput::default(), |_| {});
+        let painter = Painter::new(ctx.clone(), LayerId::background(), Rect::EVERYTHING);
+        let mut meta = Metadata::default();
+        let style = SettingsStyle::new()
+            .with_edge_label_min_size(Some(6.))
+            .with_edge_label_color(Some(Color32::RED));
+
+        let e: Edge<(), ()> = Edge::new(()).with_label("weight".to_string());
+        // the display takes the label from the properties when drawn
+        let display = DefaultEdgeShape::from(e.props().clone());
+        let label = |meta: &Metadata| {
+            let draw_ctx = DrawContext {
+                ctx: &ctx,
+                painter: &painter,
+                style: &style,
+                is_directed: true,
+                meta,
+            };
+            display.label(Pos2::ZERO, 10., Color32::WHITE, &draw_ctx)
+        };
+
+        let shapes = label(&meta);
+        let [Shape::Text(text)] = shapes.as_slice() else {
+            panic!("expected a single text shape");
+        };
+        assert_eq!(text.fallback_color, Color32::RED);
+
+        // 10 canvas units are 5 pixels
+        meta.zoom = 0.5;
+        assert!(label(&meta).is_empty());
+    }
 
     #[test]
     fn test_distance_segment_to_point() {
@@ -339,6 +639,77 @@ mod tests {
         assert_eq!(distance_segment_to_point(segment_1, segment_2, point), 0.0);
     }
 
+    #[test]
+    fn test_distance_segment_to_point_zero_length_segment() {
+        let segment = Pos2::new(1.0, 2.0);
+        let point = Pos2::new(4.0, 6.0);
+        assert_eq!(distance_segment_to_point(segment, segment, point), 5.0);
+        assert_eq!(distance_segment_to_point(segment, segment, segment), 0.0);
+    }
+
+    #[test]
+    fn test_connector_points_offset_by_radii() {
+        let mut start: Node<(), ()> = Node::new(()).with_radius(5.);
+        let mut end: Node<(), ()> = Node::new(()).with_radius(12.);
+        end.set_location(Pos2::new(100., 0.));
+        for n in [&mut start, &mut end] {
+            let props = n.props().clone();
+            DisplayNode::<(), (), Directed, DefaultIx>::update(n.display_mut(), &props);
+        }
+
+        let (from, to, dir) = connector_points(&start, &end);
+        assert_eq!(dir, Vec2::new(1., 0.));
+        assert!(from.distance(Pos2::new(5., 0.)) < 1e-4);
+        assert!(to.distance(Pos2::new(88., 0.)) < 1e-4);
+
+        // nodes overlapping each other leave no room for the line body
+        end.set_location(Pos2::new(10., 0.));
+        let props = end.props().clone();
+        DisplayNode::<(), (), Directed, DefaultIx>::update(end.display_mut(), &props);
+        let (from, to, dir) = connector_points(&start, &end);
+        assert_eq!(straight_bounds(from, to, dir, None), None);
+    }
+
+    #[test]
+    fn test_straight_bounds_distant_nodes() {
+        let (start, end) = (Pos2::new(0., 0.), Pos2::new(100., 0.));
+        let dir = Vec2::new(1., 0.);
+        assert_eq!(
+            straight_bounds(start, end, dir, Some(15.)),
+            Some(((start, end), true))
+        );
+        assert_eq!(
+            straight_bounds(start, end, dir, None),
+            Some(((start, end), true))
+        );
+    }
+
+    #[test]
+    fn test_straight_bounds_close_nodes_draw_only_tip() {
+        let (start, end) = (Pos2::new(0., 0.), Pos2::new(10., 0.));
+        let dir = Vec2::new(1., 0.);
+        assert_eq!(
+            straight_bounds(start, end, dir, Some(15.)),
+            Some(((Pos2::new(-5., 0.), end), false))
+        );
+        assert_eq!(
+            straight_bounds(start, end, dir, None),
+            Some(((start, end), true))
+        );
+    }
+
+    #[test]
+    fn test_straight_bounds_overlapping_nodes() {
+        // boundary points swapped because the nodes overlap
+        let (start, end) = (Pos2::new(5., 0.), Pos2::new(-5., 0.));
+        let dir = Vec2::new(1., 0.);
+        assert_eq!(
+            straight_bounds(start, end, dir, Some(15.)),
+            Some(((Pos2::new(-20., 0.), end), false))
+        );
+        assert_eq!(straight_bounds(start, end, dir, None), None);
+    }
+
     #[test]
     fn test_hypot2() {
         let a = Vec2::new(0.0, 1.0);
@@ -1,8 +1,15 @@
 use std::f32::consts::PI;
 
-use egui::{epaint::CubicBezierShape, Color32, Pos2, Shape, Stroke, Vec2};
+use egui::{
+    epaint::{CubicBezierShape, PathStroke},
+    Color32, Pos2, Shape, Stroke, Vec2,
+};
 
-use crate::Metadata;
+use crate::{settings::CurveSegments, Metadata};
+
+const TICK_ANGLE: f32 = PI / 5.;
+/// Minimal width of edges on the screen so that thin edges stay visible when zoomed out.
+const MIN_SCREEN_WIDTH: f32 = 1.;
 
 enum EdgeShapeProps {
     Straight {
@@ -41,6 +48,7 @@ pub struct EdgeShapeBuilder<'a> {
     tip: Option<&'a TipProps>,
     stroke: Stroke,
     scaler: Option<&'a Metadata>,
+    skip_body: bool,
 }
 
 impl<'a> EdgeShapeBuilder<'a> {
@@ -90,6 +98,13 @@ impl<'a> EdgeShapeBuilder<'a> {
         self
     }
 
+    /// Draws only the tip of a straight edge omitting its line body.
+    pub fn without_body(mut self) -> Self {
+        self.skip_body = true;
+
+        self
+    }
+
     pub fn with_tip(mut self, tip_props: &'a TipProps) -> Self {
         self.tip = Some(tip_props);
 
@@ -122,7 +137,9 @@ impl<'a> EdgeShapeBuilder<'a> {
         };
 
         if let Some(scaler) = self.scaler {
-            stroke.width = scaler.canvas_to_screen_size(stroke.width);
+            stroke.width = scaler
+                .canvas_to_screen_size(stroke.width)
+                .max(MIN_SCREEN_WIDTH);
             points_line = points_line
                 .iter()
                 .map(|p| scaler.canvas_to_screen_pos(*p))
@@ -133,10 +150,12 @@ impl<'a> EdgeShapeBuilder<'a> {
                 .collect();
         }
 
-        res.push(Shape::line_segment(
-            [points_line[0], points_line[1]],
-            stroke,
-        ));
+        if !self.skip_body {
+            res.push(Shape::line_segment(
+                [points_line[0], points_line[1]],
+                stroke,
+            ));
+        }
         if !points_tip.is_empty() {
             res.push(Shape::convex_polygon(
                 points_tip,
@@ -175,12 +194,39 @@ impl<'a> EdgeShapeBuilder<'a> {
         let mut control_point1 = Pos2::new(node_center.x + loop_size, node_center.y - loop_size);
         let mut control_point2 = Pos2::new(node_center.x - loop_size, node_center.y - loop_size);
 
+        // the curve is drawn from `edge_end` around to `edge_start` where the tip is placed
+        let mut points_tip = match self.tip {
+            Some(tip_props) => {
+                let tip_end = edge_start;
+                let tip_dir = (tip_end - control_point2).normalized();
+
+                let arrow_tip_dir_1 = rotate_vector(tip_dir, tip_props.angle) * tip_props.size;
+                let arrow_tip_dir_2 = rotate_vector(tip_dir, -tip_props.angle) * tip_props.size;
+
+                // replace end of the loop with start of tip
+                edge_start = tip_end - tip_props.size * tip_dir;
+
+                vec![
+                    tip_end,
+                    tip_end - arrow_tip_dir_1,
+                    tip_end - arrow_tip_dir_2,
+                ]
+            }
+            None => vec![],
+        };
+
         if let Some(scaler) = self.scaler {
-            stroke.width = scaler.canvas_to_screen_size(stroke.width);
+            stroke.width = scaler
+                .canvas_to_screen_size(stroke.width)
+                .max(MIN_SCREEN_WIDTH);
             edge_end = scaler.canvas_to_screen_pos(edge_end);
             control_point1 = scaler.canvas_to_screen_pos(control_point1);
             control_point2 = scaler.canvas_to_screen_pos(control_point2);
             edge_start = scaler.canvas_to_screen_pos(edge_start);
+            points_tip = points_tip
+                .iter()
+                .map(|p| scaler.canvas_to_screen_pos(*p))
+                .collect();
         }
 
         res.push(
@@ -192,6 +238,14 @@ impl<'a> EdgeShapeBuilder<'a> {
             )
             .into(),
         );
+        if !points_tip.is_empty() {
+            res.push(Shape::convex_polygon(
+                points_tip,
+                stroke.color,
+                Stroke::default(),
+            ));
+        }
+
         res
     }
 
@@ -207,14 +261,18 @@ impl<'a> EdgeShapeBuilder<'a> {
         let height = dir_p * curve_size * order as f32;
         let cp = center_point + height;
 
-        let cp_start = cp - dir * curve_size / (order as f32 * dist * 0.5);
-        let cp_end = cp + dir * curve_size / (order as f32 * dist * 0.5);
+        // spread of the control points along the edge, parallel edges with a higher order
+        // bulge further out from the straight segment
+        let spread = curve_size / (order as f32 * dist.length() * 0.5);
+        let cp_start = cp - dir * spread;
+        let cp_end = cp + dir * spread;
 
         let mut points_curve = vec![start, cp_start, cp_end, end];
 
         let mut points_tip = match self.tip {
             Some(tip_props) => {
-                let tip_dir = (end - cp).normalized();
+                // tip is tangent to the curve at its end
+                let tip_dir = (end - cp_end).normalized();
 
                 let arrow_tip_dir_1 = rotate_vector(tip_dir, tip_props.angle) * tip_props.size;
                 let arrow_tip_dir_2 = rotate_vector(tip_dir, -tip_props.angle) * tip_props.size;
@@ -231,7 +289,9 @@ impl<'a> EdgeShapeBuilder<'a> {
         };
 
         if let Some(scaler) = self.scaler {
-            stroke.width = scaler.canvas_to_screen_size(stroke.width);
+            stroke.width = scaler
+                .canvas_to_screen_size(stroke.width)
+                .max(MIN_SCREEN_WIDTH);
             points_curve = points_curve
                 .iter()
                 .map(|p| scaler.canvas_to_screen_pos(*p))
@@ -285,9 +345,343 @@ impl<'a> EdgeShapeBuilder<'a> {
     }
 }
 
+/// Places chevrons pointing along the polyline every `spacing` pixels of its length.
+///
+/// No chevron is placed closer than half of the `spacing` to the end of the polyline
+/// to not interfere with the edge tip.
+pub fn direction_ticks(points: &[Pos2], spacing: f32, size: f32, stroke: Stroke) -> Vec<Shape> {
+    let mut res = vec![];
+    if spacing <= 0. {
+        return res;
+    }
+
+    let total = points.windows(2).map(|w| w[0].distance(w[1])).sum::<f32>();
+    let last = total - spacing / 2.;
+
+    let mut next = spacing;
+    let mut travelled = 0.;
+    for w in points.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let len = a.distance(b);
+        if len == 0. {
+            continue;
+        }
+
+        let dir = (b - a) / len;
+        while next <= travelled + len && next <= last {
+            let tip = a + dir * (next - travelled);
+            let wing_1 = tip - rotate_vector(dir, TICK_ANGLE) * size;
+            let wing_2 = tip - rotate_vector(dir, -TICK_ANGLE) * size;
+            res.push(Shape::line(vec![wing_1, tip, wing_2], stroke));
+
+            next += spacing;
+        }
+
+        travelled += len;
+    }
+
+    res
+}
+
 /// rotates vector by angle
 fn rotate_vector(vec: Vec2, angle: f32) -> Vec2 {
     let cos = angle.cos();
     let sin = angle.sin();
     Vec2::new(cos * vec.x - sin * vec.y, sin * vec.x + cos * vec.y)
 }
+
+/// Returns points of the polyline approximating the `curve` with the given number of segments.
+pub fn curve_points(curve: &CubicBezierShape, segments: CurveSegments) -> Vec<Pos2> {
+    let count = match segments {
+        CurveSegments::Auto => return curve.flatten(None),
+        CurveSegments::Fixed(count) => count,
+        CurveSegments::Adaptive {
+            pixels_per_segment,
+            min,
+            max,
+        } => {
+            // length of the control polygon bounds the length of the curve from above
+            let length = curve
+                .points
+                .windows(2)
+                .map(|w| w[0].distance(w[1]))
+                .sum::<f32>();
+            // unlike `clamp` it does not panic when the bounds are inverted, `max` wins then
+            ((length / pixels_per_segment).ceil() as usize)
+                .max(min)
+                .min(max)
+        }
+    }
+    .max(1);
+
+    (0..=count)
+        .map(|i| curve.sample(i as f32 / count as f32))
+        .collect()
+}
+
+/// Splits the path given by `points` into as many parts of equal length as there are `colors`
+/// and strokes each part with the corresponding color.
+pub fn gradient_segments(points: &[Pos2], colors: &[Color32], width: f32) -> Vec<Shape> {
+    let lengths = points
+        .windows(2)
+        .map(|w| w[0].distance(w[1]))
+        .collect::<Vec<_>>();
+    let total = lengths.iter().sum::<f32>();
+    if colors.is_empty() || total <= 0. {
+        return vec![];
+    }
+
+    let step = total / colors.len() as f32;
+    let mut res = Vec::with_capacity(colors.len());
+    let mut part = vec![points[0]];
+    let mut color_idx = 0;
+    let mut travelled = 0.;
+    for (i, len) in lengths.iter().enumerate() {
+        let (a, b) = (points[i], points[i + 1]);
+        while color_idx + 1 < colors.len() && (color_idx + 1) as f32 * step <= travelled + len {
+            let split = a + (b - a) * (((color_idx + 1) as f32 * step - travelled) / len);
+            part.push(split);
+            let finished = std::mem::replace(&mut part, vec![split]);
+            res.push(Shape::line(finished, Stroke::new(width, colors[color_idx])));
+            color_idx += 1;
+        }
+        part.push(b);
+        travelled += len;
+    }
+    res.push(Shape::line(part, Stroke::new(width, colors[color_idx])));
+
+    res
+}
+
+/// Breaks the path given by `points` into dashes of `dash` length separated by gaps of `gap`
+/// length, starting `phase` into the pattern.
+///
+/// Returns the dashes together with the phase at the end of the path, so that the pattern
+/// continues seamlessly on the next part of a path split into several.
+pub fn dash_segments(
+    points: &[Pos2],
+    stroke: &PathStroke,
+    dash: f32,
+    gap: f32,
+    phase: f32,
+) -> (Vec<Shape>, f32) {
+    let period = dash + gap;
+    if dash <= 0. || gap < 0. {
+        return (vec![], phase);
+    }
+
+    let mut res = vec![];
+    let mut phase = phase % period;
+    for w in points.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let len = a.distance(b);
+        if len == 0. {
+            continue;
+        }
+
+        let dir = (b - a) / len;
+        let mut travelled = 0.;
+        while travelled < len {
+            let in_dash = phase < dash;
+            let step = if in_dash {
+                dash - phase
+            } else {
+                period - phase
+            }
+            .min(len - travelled);
+            if in_dash {
+                res.push(Shape::line_segment(
+                    [a + dir * travelled, a + dir * (travelled + step)],
+                    stroke.clone(),
+                ));
+            }
+            travelled += step;
+            phase = (phase + step) % period;
+        }
+    }
+
+    (res, phase)
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::epaint::ColorMode;
+
+    use super::*;
+
+    #[test]
+    fn test_dash_segments() {
+        let stroke = PathStroke::new(1., Color32::WHITE);
+        let ends = |shapes: &[Shape]| {
+            shapes
+                .iter()
+                .map(|s| match s {
+                    Shape::LineSegment { points, .. } => (points[0].x, points[1].x),
+                    _ => panic!("expected line segments"),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let points = [Pos2::new(0., 0.), Pos2::new(20., 0.)];
+        let (dashes, phase) = dash_segments(&points, &stroke, 4., 2., 0.);
+        assert_eq!(
+            ends(&dashes),
+            vec![(0., 4.), (6., 10.), (12., 16.), (18., 20.)]
+        );
+        assert_eq!(phase, 2.);
+
+        // the pattern continues on the next part of the path
+        let (first, phase) = dash_segments(&points[..], &stroke, 4., 2., 3.);
+        let next = [Pos2::new(20., 0.), Pos2::new(30., 0.)];
+        let (second, _) = dash_segments(&next, &stroke, 4., 2., phase);
+        assert_eq!(ends(&first).first(), Some(&(0., 1.)));
+        assert_eq!(ends(&second), vec![(21., 25.), (27., 30.)]);
+    }
+
+    #[test]
+    fn test_direction_ticks_spacing() {
+        let points = [Pos2::new(0., 0.), Pos2::new(50., 0.), Pos2::new(100., 0.)];
+        let ticks = direction_ticks(&points, 30., 5., Stroke::new(1., Color32::WHITE));
+        assert_eq!(ticks.len(), 2);
+
+        let Shape::Path(first) = &ticks[0] else {
+            panic!("invalid shape type")
+        };
+        assert_eq!(first.points[1], Pos2::new(30., 0.));
+    }
+
+    #[test]
+    fn test_curved_tip_is_tangent() {
+        let tip = TipProps {
+            size: 5.,
+            angle: 0.3,
+        };
+        let bounds = (Pos2::new(0., 0.), Pos2::new(100., 0.));
+        for order in 1..4 {
+            let shapes = EdgeShapeBuilder::new(Stroke::new(1., Color32::WHITE))
+                .curved(bounds, 20., order)
+                .with_tip(&tip)
+                .build();
+
+            let Shape::CubicBezier(curve) = &shapes[0] else {
+                panic!("invalid shape type")
+            };
+            let Shape::Path(tip) = &shapes[1] else {
+                panic!("invalid shape type")
+            };
+            assert!(curve
+                .points
+                .iter()
+                .all(|p| p.x.is_finite() && p.y.is_finite()));
+
+            let tangent = (curve.points[3] - curve.points[2]).normalized();
+            let tip_dir = (tip.points[0] - curve.points[3]).normalized();
+            assert!(
+                (tangent - tip_dir).length() < 1e-4,
+                "{tangent:?} != {tip_dir:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_looped_tip_ends_loop() {
+        let tip = TipProps {
+            size: 3.,
+            angle: 0.3,
+        };
+        let shapes = EdgeShapeBuilder::new(Stroke::new(1., Color32::WHITE))
+            .looped(Pos2::new(10., 10.), 5., 3., 0)
+            .with_tip(&tip)
+            .build();
+        assert_eq!(shapes.len(), 2);
+
+        let Shape::CubicBezier(curve) = &shapes[0] else {
+            panic!("invalid shape type")
+        };
+        let Shape::Path(tip) = &shapes[1] else {
+            panic!("invalid shape type")
+        };
+        let tangent = (curve.points[3] - curve.points[2]).normalized();
+        let tip_dir = (tip.points[0] - curve.points[3]).normalized();
+        assert!(
+            (tangent - tip_dir).length() < 1e-4,
+            "{tangent:?} != {tip_dir:?}"
+        );
+        assert!(((tip.points[0] - curve.points[3]).length() - 3.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_curve_points() {
+        let curve = CubicBezierShape::from_points_stroke(
+            [
+                Pos2::new(0., 0.),
+                Pos2::new(0., 50.),
+                Pos2::new(100., 50.),
+                Pos2::new(100., 0.),
+            ],
+            false,
+            Color32::TRANSPARENT,
+            Stroke::new(1., Color32::WHITE),
+        );
+
+        let fixed = curve_points(&curve, CurveSegments::Fixed(8));
+        assert_eq!(fixed.len(), 9);
+        assert_eq!(fixed.first(), Some(&Pos2::new(0., 0.)));
+        assert_eq!(fixed.last(), Some(&Pos2::new(100., 0.)));
+
+        // control polygon is 200 pixels long
+        let adaptive = |min, max| {
+            curve_points(
+                &curve,
+                CurveSegments::Adaptive {
+                    pixels_per_segment: 10.,
+                    min,
+                    max,
+                },
+            )
+            .len()
+        };
+        assert_eq!(adaptive(2, 64), 21);
+        assert_eq!(adaptive(2, 8), 9);
+        assert_eq!(adaptive(32, 64), 33);
+        // inverted bounds
+        assert_eq!(adaptive(64, 8), 9);
+    }
+
+    #[test]
+    fn test_gradient_segments() {
+        let points = [Pos2::new(0., 0.), Pos2::new(30., 0.), Pos2::new(100., 0.)];
+        let colors = [Color32::RED, Color32::GREEN, Color32::BLUE, Color32::WHITE];
+        let segments = gradient_segments(&points, &colors, 1.);
+        assert_eq!(segments.len(), colors.len());
+
+        let expected_points = [
+            vec![Pos2::new(0., 0.), Pos2::new(25., 0.)],
+            vec![Pos2::new(25., 0.), Pos2::new(30., 0.), Pos2::new(50., 0.)],
+            vec![Pos2::new(50., 0.), Pos2::new(75., 0.)],
+            vec![Pos2::new(75., 0.), Pos2::new(100., 0.)],
+        ];
+        for ((segment, expected), color) in segments.iter().zip(expected_points).zip(colors) {
+            let Shape::Path(path) = segment else {
+                panic!("invalid shape type")
+            };
+            assert_eq!(path.points, expected);
+            assert!(matches!(path.stroke.color, ColorMode::Solid(c) if c == color));
+        }
+    }
+
+    #[test]
+    fn test_gradient_segments_degenerate_path() {
+        let colors = [Color32::RED, Color32::GREEN];
+        let points = [Pos2::new(10., 10.), Pos2::new(10., 10.)];
+        assert!(gradient_segments(&points, &colors, 1.).is_empty());
+        assert!(gradient_segments(&points[..1], &colors, 1.).is_empty());
+    }
+
+    #[test]
+    fn test_direction_ticks_short_edge() {
+        let points = [Pos2::new(0., 0.), Pos2::new(10., 0.)];
+        let ticks = direction_ticks(&points, 30., 5., Stroke::new(1., Color32::WHITE));
+        assert!(ticks.is_empty());
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use egui::{epaint::TextShape, Color32, Galley, Pos2, Rounding, Shape};
+
+use crate::draw::DrawContext;
+
+/// Creates shapes for a label placed at `pos`.
+///
+/// If [`crate::SettingsStyle::with_label_background`] is set, a background derived from
+/// the galley bounds is placed behind the text.
+pub fn label_shapes(
+    pos: Pos2,
+    galley: Arc<Galley>,
+    color: Color32,
+    ctx: &DrawContext,
+) -> Vec<Shape> {
+    let mut res = Vec::with_capacity(2);
+
+    if let Some(background) = &ctx.style.label_background {
+        let rect = galley
+            .rect
+            .translate(pos.to_vec2())
+            .expand(background.padding);
+        res.push(Shape::rect_filled(
+            rect,
+            Rounding::same(background.rounding),
+            background.color,
+        ));
+    }
+
+    res.push(TextShape::new(pos, galley, color).into());
+
+    res
+}
@@ -1,13 +1,36 @@
+use std::f32::consts::SQRT_2;
+
 use egui::{
-    epaint::{CircleShape, TextShape},
-    Color32, FontFamily, FontId, Pos2, Shape, Stroke, Vec2,
+    epaint::CircleShape, Color32, FontFamily, FontId, Pos2, Rect, Shape, Stroke, TextureId, Vec2,
 };
 use petgraph::{stable_graph::IndexType, EdgeType};
 
-use crate::{draw::drawer::DrawContext, DisplayNode, NodeProps};
+use crate::{draw::drawer::DrawContext, DisplayNode, NodeProps, NodeShape};
+
+use super::label::label_shapes;
+
+/// Radius of nodes without [`NodeProps::radius`].
+const DEFAULT_RADIUS: f32 = 5.;
+
+/// Labels with a smaller font size on the screen are not drawn to avoid clutter when zoomed out.
+const MIN_LABEL_SIZE: f32 = 4.;
 
 /// This is the default node shape which is used to display nodes in the graph.
 ///
+/// The node is drawn as a circle, or another [`NodeShape`] inscribed in it, see
+/// [`crate::Node::with_shape`]. It is filled with the node color, see [`crate::Node::set_color`],
+/// or with the inactive widget color of the egui style if the color is not set. The fill is never
+/// changed by interactions. Selected, dragged and hovered nodes are marked with a ring around the
+/// outline using the selection, active and hovered widget colors respectively. This way clients
+/// can rely on the fill to encode a category of the node while the ring encodes its interaction
+/// state.
+///
+/// Nodes with a texture, see [`crate::Node::with_texture`], are drawn as the image scaled to the
+/// square inscribed in the node circle, surrounded by a thin border in the fill color or the ring.
+///
+/// If glow is enabled with [`crate::SettingsStyle::with_glow`], nodes with a glow intensity
+/// are drawn over a halo of concentric translucent circles in the fill color.
+///
 /// You can use this implementation as an example for implementing your own custom node shapes.
 #[derive(Clone, Debug)]
 pub struct DefaultNodeShape {
@@ -15,7 +38,11 @@ pub struct DefaultNodeShape {
 
     pub selected: bool,
     pub dragged: bool,
+    pub hovered: bool,
     pub color: Option<Color32>,
+    pub glow: Option<f32>,
+    pub shape: NodeShape,
+    pub texture: Option<TextureId>,
 
     pub label_text: String,
 
@@ -29,10 +56,14 @@ impl<N: Clone> From<NodeProps<N>> for DefaultNodeShape {
             pos: node_props.location(),
             selected: node_props.selected,
             dragged: node_props.dragged,
+            hovered: node_props.hovered,
             label_text: node_props.label.to_string(),
             color: node_props.color(),
+            glow: node_props.glow(),
+            shape: node_props.shape(),
+            texture: node_props.texture(),
 
-            radius: 5.0,
+            radius: node_props.radius().unwrap_or(DEFAULT_RADIUS),
         }
     }
 }
@@ -41,42 +72,83 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType> DisplayNode<N, E, Ty, Ix>
     for DefaultNodeShape
 {
     fn is_inside(&self, pos: Pos2) -> bool {
-        is_inside_circle(self.pos, self.radius, pos)
+        match shape_vertices(self.shape, self.pos, self.radius) {
+            Some(vertices) => is_inside_polygon(&vertices, pos),
+            None => is_inside_circle(self.pos, self.radius, pos),
+        }
     }
 
     fn closest_boundary_point(&self, dir: Vec2) -> Pos2 {
-        closest_point_on_circle(self.pos, self.radius, dir)
+        match shape_vertices(self.shape, self.pos, self.radius) {
+            Some(vertices) => closest_point_on_polygon(self.pos, &vertices, dir),
+            None => closest_point_on_circle(self.pos, self.radius, dir),
+        }
     }
 
     fn shapes(&mut self, ctx: &DrawContext) -> Vec<Shape> {
         let mut res = Vec::with_capacity(2);
 
-        let is_interacted = self.selected || self.dragged;
+        let style = ctx.ctx.style();
+        let visuals = &style.visuals;
 
-        let style = if is_interacted {
-            ctx.ctx.style().visuals.widgets.active
-        } else {
-            ctx.ctx.style().visuals.widgets.inactive
-        };
+        // fill encodes the category of the node and is not affected by interactions
+        let fill = self.color.unwrap_or(
+            ctx.style
+                .color_node
+                .unwrap_or(visuals.widgets.inactive.fg_stroke.color),
+        );
 
-        let color = if let Some(c) = self.color {
-            c
+        // ring encodes the interaction state of the node
+        let ring_color = if self.selected {
+            Some(
+                ctx.style
+                    .color_selected
+                    .unwrap_or(visuals.selection.stroke.color),
+            )
+        } else if self.dragged {
+            Some(visuals.widgets.active.fg_stroke.color)
+        } else if self.hovered {
+            Some(visuals.widgets.hovered.fg_stroke.color)
         } else {
-            style.fg_stroke.color
+            None
         };
 
         let circle_center = ctx.meta.canvas_to_screen_pos(self.pos);
         let circle_radius = ctx.meta.canvas_to_screen_size(self.radius);
-        let circle_shape = CircleShape {
-            center: circle_center,
-            radius: circle_radius,
-            fill: color,
-            stroke: Stroke::default(),
-        };
-        res.push(circle_shape.into());
 
-        let label_visible = ctx.style.labels_always || self.selected || self.dragged;
-        if !label_visible {
+        if let Some(glow) = self.glow.filter(|_| ctx.style.enable_glow) {
+            res.extend(glow_shapes(circle_center, circle_radius, fill, glow));
+        }
+        let ring = ring_color.map(|c| Stroke::new(circle_radius / 4., c));
+        if let Some(texture) = self.texture {
+            // textured node keeps only a thin border in the fill color
+            let border = ring.unwrap_or(Stroke::new(circle_radius / 8., fill));
+            res.push(self.outline_shape(
+                circle_center,
+                circle_radius,
+                Color32::TRANSPARENT,
+                border,
+            ));
+
+            let image_rect =
+                Rect::from_center_size(circle_center, Vec2::splat(circle_radius * SQRT_2));
+            let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1., 1.));
+            res.push(Shape::image(texture, image_rect, uv, Color32::WHITE));
+        } else {
+            let stroke = ring.unwrap_or(Stroke::NONE);
+            res.push(self.outline_shape(circle_center, circle_radius, fill, stroke));
+        }
+
+        let color = ctx
+            .style
+            .label_color
+            .unwrap_or_else(|| ring_color.unwrap_or(fill));
+
+        let label_visible = ctx.style.labels_always
+            || self.selected
+            || self.dragged
+            || (self.hovered && ctx.style.labels_on_hover);
+        if !label_visible || circle_radius < MIN_LABEL_SIZE {
             return res;
         }
 
@@ -94,8 +166,7 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType> DisplayNode<N, E, Ty, Ix>
             circle_center.y - circle_radius * 2.,
         );
 
-        let label_shape = TextShape::new(label_pos, galley, color);
-        res.push(label_shape.into());
+        res.extend(label_shapes(label_pos, galley, color, ctx));
 
         res
     }
@@ -104,11 +175,114 @@ impl<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType> DisplayNode<N, E, Ty, Ix>
         self.pos = state.location();
         self.selected = state.selected;
         self.dragged = state.dragged;
+        self.hovered = state.hovered;
         self.label_text = state.label.to_string();
         self.color = state.color();
+        self.glow = state.glow();
+        self.shape = state.shape();
+        self.texture = state.texture();
+        if let Some(radius) = state.radius() {
+            self.radius = radius;
+        }
+    }
+}
+
+impl DefaultNodeShape {
+    /// Creates the outline of the node in screen coordinates.
+    fn outline_shape(&self, center: Pos2, radius: f32, fill: Color32, stroke: Stroke) -> Shape {
+        match shape_vertices(self.shape, center, radius) {
+            Some(vertices) => Shape::convex_polygon(vertices, fill, stroke),
+            None => CircleShape {
+                center,
+                radius,
+                fill,
+                stroke,
+            }
+            .into(),
+        }
+    }
+}
+
+const GLOW_LAYERS: usize = 4;
+
+/// Fakes a blurred halo with concentric translucent circles, largest first.
+///
+/// Both the extent and the opacity of the halo grow with the `intensity`.
+fn glow_shapes(center: Pos2, radius: f32, color: Color32, intensity: f32) -> Vec<Shape> {
+    let intensity = intensity.clamp(0., 1.);
+    if intensity == 0. {
+        return vec![];
+    }
+
+    let extent = radius * 2. * intensity;
+    let alpha = 255. * intensity / GLOW_LAYERS as f32;
+    (1..=GLOW_LAYERS)
+        .rev()
+        .map(|layer| {
+            let layer_radius = radius + extent * layer as f32 / GLOW_LAYERS as f32;
+            let fill =
+                Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), alpha as u8);
+            Shape::circle_filled(center, layer_radius, fill)
+        })
+        .collect()
+}
+
+/// Returns vertices of the outline inscribed in the circle, or `None` for a circle.
+fn shape_vertices(shape: NodeShape, center: Pos2, radius: f32) -> Option<Vec<Pos2>> {
+    let angles: &[f32] = match shape {
+        NodeShape::Circle => return None,
+        NodeShape::Square => &[45., 135., 225., 315.],
+        NodeShape::Triangle => &[270., 30., 150.],
+    };
+
+    Some(
+        angles
+            .iter()
+            .map(|deg| center + Vec2::angled(deg.to_radians()) * radius)
+            .collect(),
+    )
+}
+
+/// Returns the point where the ray from the `center` inside the convex polygon
+/// in direction `dir` crosses the outline.
+fn closest_point_on_polygon(center: Pos2, vertices: &[Pos2], dir: Vec2) -> Pos2 {
+    let dir = dir.normalized();
+    let t = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .filter_map(|(a, b)| {
+            let side = *b - *a;
+            let denom = cross(dir, side);
+            if denom.abs() < f32::EPSILON {
+                return None;
+            }
+            let to_a = *a - center;
+            let t = cross(to_a, side) / denom;
+            let s = cross(to_a, dir) / denom;
+            (t >= 0. && (0. ..=1.).contains(&s)).then_some(t)
+        })
+        .fold(f32::INFINITY, f32::min);
+
+    if t.is_finite() {
+        center + dir * t
+    } else {
+        center
     }
 }
 
+fn is_inside_polygon(vertices: &[Pos2], pos: Pos2) -> bool {
+    // inside a convex polygon the point is on the same side of all edges
+    let mut sides = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| cross(*b - *a, pos - *a));
+    sides.clone().all(|s| s >= 0.) || sides.all(|s| s <= 0.)
+}
+
+fn cross(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
 fn closest_point_on_circle(center: Pos2, radius: f32, dir: Vec2) -> Pos2 {
     center + dir.normalized() * radius
 }
@@ -121,7 +295,9 @@ fn is_inside_circle(center: Pos2, radius: f32, pos: Pos2) -> bool {
 #[cfg(test)]
 mod test {
     use super::*;
-    use egui::Pos2;
+    use crate::{Metadata, Node, SettingsStyle};
+    use egui::{Context, LayerId, Painter, Pos2, RawInput, Visuals};
+    use petgraph::{stable_graph::DefaultIx, Directed};
 
     #[test]
     fn test_closest_point_on_circle() {
@@ -139,6 +315,55 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_square_hit_testing() {
+        let center = Pos2::new(0.0, 0.0);
+        let vertices = shape_vertices(NodeShape::Square, center, 10.0).unwrap();
+
+        // corner of the square is outside of the inscribed circle of the square
+        let corner = Pos2::new(6.5, 6.5);
+        assert!(is_inside_polygon(&vertices, corner));
+        assert!(!is_inside_polygon(&vertices, Pos2::new(7.5, 0.0)));
+
+        let half_side = 10.0 / 2.0_f32.sqrt();
+        let boundary = closest_point_on_polygon(center, &vertices, Vec2::new(1.0, 0.0));
+        assert!((boundary - Pos2::new(half_side, 0.0)).length() < 1e-4);
+        let boundary = closest_point_on_polygon(center, &vertices, Vec2::new(1.0, 1.0));
+        assert!((boundary - vertices[0]).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_triangle_hit_testing() {
+        let center = Pos2::new(0.0, 0.0);
+        let vertices = shape_vertices(NodeShape::Triangle, center, 10.0).unwrap();
+
+        assert!(is_inside_polygon(&vertices, Pos2::new(0.0, -9.0)));
+        assert!(!is_inside_polygon(&vertices, Pos2::new(0.0, 9.0)));
+        assert!(shape_vertices(NodeShape::Circle, center, 10.0).is_none());
+
+        // bottom side is half of the radius below the center
+        let boundary = closest_point_on_polygon(center, &vertices, Vec2::new(0.0, 1.0));
+        assert!((boundary - Pos2::new(0.0, 5.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_glow_shapes() {
+        let center = Pos2::new(0.0, 0.0);
+        assert!(glow_shapes(center, 10.0, Color32::RED, 0.0).is_empty());
+
+        let shapes = glow_shapes(center, 10.0, Color32::RED, 1.0);
+        assert_eq!(shapes.len(), GLOW_LAYERS);
+        let radii = shapes
+            .iter()
+            .map(|s| match s {
+                Shape::Circle(c) => c.radius,
+                _ => panic!("expected circle"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(radii.first(), Some(&30.0));
+        assert!(radii.windows(2).all(|w| w[0] > w[1] && w[1] > 10.0));
+    }
+
     #[test]
     fn test_is_inside_circle() {
         assert!(is_inside_circle(
@@ -157,4 +382,89 @@ mod test {
             Pos2::new(0.0, 10.0)
         ));
     }
+
+    #[test]
+    fn test_style_colors() {
+        let ctx = Context::default();
+        let _ = ctx.run(RawInput::default(), |_| {});
+        let painter = Painter::new(ctx.clone(), LayerId::background(), Rect::EVERYTHING);
+        let meta = Metadata::default();
+        let style = SettingsStyle::new()
+            .with_color_node(Some(Color32::GREEN))
+            .with_color_selected(Some(Color32::RED));
+        let draw_ctx = DrawContext {
+            ctx: &ctx,
+            painter: &painter,
+            style: &style,
+            is_directed: true,
+            meta: &meta,
+        };
+
+        let node: Node<(), ()> = Node::new(());
+        let mut shape = node.display().clone();
+        shape.selected = true;
+        let shapes = DisplayNode::<(), (), Directed, DefaultIx>::shapes(&mut shape, &draw_ctx);
+        let Some(Shape::Circle(circle)) = shapes.first() else {
+            panic!("expected the node circle first");
+        };
+        assert_eq!(circle.fill, Color32::GREEN);
+        assert_eq!(circle.stroke.color, Color32::RED);
+
+        // own color of the node wins over the style
+        shape.color = Some(Color32::BLUE);
+        let shapes = DisplayNode::<(), (), Directed, DefaultIx>::shapes(&mut shape, &draw_ctx);
+        let Some(Shape::Circle(circle)) = shapes.first() else {
+            panic!("expected the node circle first");
+        };
+        assert_eq!(circle.fill, Color32::BLUE);
+    }
+
+    #[test]
+    fn test_style_from_visuals() {
+        // the context uses the dark theme by default
+        let ctx = Context::default();
+        let _ = ctx.run(RawInput::default(), |_| {});
+        let painter = Painter::new(ctx.clone(), LayerId::background(), Rect::EVERYTHING);
+        let meta = Metadata::default();
+        let style = SettingsStyle::light();
+        let draw_ctx = DrawContext {
+            ctx: &ctx,
+            painter: &painter,
+            style: &style,
+            is_directed: true,
+            meta: &meta,
+        };
+
+        let node: Node<(), ()> = Node::new(());
+        let mut shape = node.display().clone();
+        let shapes = DisplayNode::<(), (), Directed, DefaultIx>::shapes(&mut shape, &draw_ctx);
+        let Some(Shape::Circle(circle)) = shapes.first() else {
+            panic!("expected the node circle first");
+        };
+        let light = Visuals::light().widgets.inactive.fg_stroke.color;
+        assert_ne!(light, Visuals::dark().widgets.inactive.fg_stroke.color);
+        assert_eq!(circle.fill, light);
+    }
+
+    #[test]
+    fn test_radius_sticks() {
+        let node: Node<(), ()> = Node::new(()).with_radius(12.);
+        let mut shape = node.display().clone();
+        assert_eq!(shape.radius, 12.);
+
+        DisplayNode::<(), (), Directed, DefaultIx>::update(&mut shape, node.props());
+        assert_eq!(shape.radius, 12.);
+        assert!(DisplayNode::<(), (), Directed, DefaultIx>::is_inside(
+            &shape,
+            Pos2::new(11., 0.)
+        ));
+
+        // radius set on the display is kept for nodes without their own radius
+        let node: Node<(), ()> = Node::new(());
+        let mut shape = node.display().clone();
+        assert_eq!(shape.radius, DEFAULT_RADIUS);
+        shape.radius = 8.;
+        DisplayNode::<(), (), Directed, DefaultIx>::update(&mut shape, node.props());
+        assert_eq!(shape.radius, 8.);
+    }
 }
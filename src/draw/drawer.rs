@@ -1,12 +1,22 @@
-use std::marker::PhantomData;
+use std::{cmp::Reverse, collections::HashSet, marker::PhantomData};
 
-use egui::{Context, Painter, Shape};
+use egui::{
+    epaint::{shape_transform::adjust_colors, TextShape},
+    Color32, Context, Painter, Pos2, Rect, Shape, Stroke, TextStyle, Vec2,
+};
 use petgraph::graph::IndexType;
+use petgraph::stable_graph::NodeIndex;
 use petgraph::EdgeType;
 
 use crate::{
+    draw::{
+        hull::hull_shape,
+        legend::{panel_shapes, MARGIN, PADDING},
+        Annotation, Minimap,
+    },
     layouts::{Layout, LayoutState},
-    settings::SettingsStyle,
+    node_size,
+    settings::{Corner, DrawOrder, SettingsStyle},
     Graph, Metadata,
 };
 
@@ -21,6 +31,19 @@ pub struct DrawContext<'a> {
     pub meta: &'a Metadata,
 }
 
+/// Minimum distance in screen points between grid lines, denser grids are thinned out.
+const MIN_GRID_SPACING: f32 = 8.;
+
+/// Padding between selected nodes and the selection hull in canvas units.
+const HULL_PADDING: f32 = 5.;
+
+/// Minimum radius in screen points of nodes drawn as dots below the level of detail threshold.
+const LOD_MIN_RADIUS: f32 = 1.;
+
+/// Padding of the widget rect in screen points within which elements are still drawn when
+/// culling, so that labels, loops and curved edges sticking into the widget are not cut off.
+const CULL_MARGIN: f32 = 50.;
+
 pub struct Drawer<'a, N, E, Ty, Ix, Nd, Ed, S, L>
 where
     N: Clone,
@@ -35,6 +58,9 @@ where
     ctx: &'a DrawContext<'a>,
     g: &'a mut Graph<N, E, Ty, Ix, Nd, Ed>,
     delayed: Vec<Shape>,
+    annotations: &'a [Annotation],
+    grid: Option<f32>,
+    minimap: Option<Minimap>,
 
     _marker: PhantomData<(Nd, Ed, L, S)>,
 }
@@ -55,14 +81,252 @@ where
             ctx,
             g,
             delayed: Vec::new(),
+            annotations: &[],
+            grid: None,
+            minimap: None,
             _marker: PhantomData,
         }
     }
 
+    /// Annotations drawn on top of the graph.
+    pub fn with_annotations(mut self, annotations: &'a [Annotation]) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Grid drawn below the graph with the given cell size in canvas coordinates.
+    pub fn with_grid(mut self, cell: Option<f32>) -> Self {
+        self.grid = cell;
+        self
+    }
+
+    /// Minimap drawn on top of the graph.
+    pub(crate) fn with_minimap(mut self, minimap: Option<Minimap>) -> Self {
+        self.minimap = minimap;
+        self
+    }
+
     pub fn draw(mut self) {
+        self.draw_grid();
+        self.draw_selection_hull();
         self.draw_edges();
+        self.draw_edge_draft();
         self.draw_nodes();
         self.draw_postponed();
+        self.draw_selection_box();
+        self.draw_zoom_box();
+        self.draw_annotations();
+        self.draw_legend();
+        self.draw_minimap();
+        self.draw_selection_hud();
+    }
+
+    fn draw_selection_hud(&self) {
+        if !self.ctx.style.show_selection_hud {
+            return;
+        }
+
+        let Some(idx) = self.g.selected_nodes().first().copied() else {
+            return;
+        };
+        let Some(n) = self.g.node(idx) else {
+            return;
+        };
+
+        let loc = n.location();
+        let text = format!(
+            "{}\nindex: {}\ndegree: {}\nposition: ({:.1}, {:.1})",
+            n.label(),
+            idx.index(),
+            self.g.incident_edges(idx).count(),
+            loc.x,
+            loc.y,
+        );
+
+        let style = self.ctx.ctx.style();
+        let font = TextStyle::Monospace.resolve(&style);
+        let text_color = style.visuals.text_color();
+        let galley = self
+            .ctx
+            .ctx
+            .fonts(|f| f.layout_no_wrap(text, font, text_color));
+
+        let size = galley.size() + Vec2::splat(2. * PADDING);
+        let frame = Corner::BottomLeft.place(self.ctx.painter.clip_rect(), size, MARGIN);
+        for s in panel_shapes(frame, &style.visuals) {
+            self.ctx.painter.add(s);
+        }
+
+        let text_pos = frame.min + Vec2::splat(PADDING);
+        self.ctx
+            .painter
+            .add(TextShape::new(text_pos, galley, text_color));
+    }
+
+    /// Draws the grid of the style settings, or the grid nodes snap to. When zoomed out the
+    /// spacing doubles until lines are at least [`MIN_GRID_SPACING`] apart on the screen.
+    fn draw_grid(&self) {
+        let Some(cell) = self.ctx.style.grid.or(self.grid) else {
+            return;
+        };
+        let Some(cell) = adaptive_grid_cell(cell, self.ctx.meta.zoom) else {
+            return;
+        };
+
+        // the painter is clipped to the widget
+        let rect = self.ctx.painter.clip_rect();
+        let min = self.ctx.meta.screen_to_canvas_pos(rect.min);
+        let max = self.ctx.meta.screen_to_canvas_pos(rect.max);
+        let color = self.ctx.style.grid_color.unwrap_or_else(|| {
+            self.ctx
+                .ctx
+                .style()
+                .visuals
+                .widgets
+                .noninteractive
+                .bg_stroke
+                .color
+                .gamma_multiply(0.5)
+        });
+        let stroke = Stroke::new(1., color);
+
+        let mut x = (min.x / cell).ceil() * cell;
+        while x <= max.x {
+            let x_screen = self.ctx.meta.canvas_to_screen_pos(Pos2::new(x, 0.)).x;
+            self.ctx.painter.vline(x_screen, rect.y_range(), stroke);
+            x += cell;
+        }
+        let mut y = (min.y / cell).ceil() * cell;
+        while y <= max.y {
+            let y_screen = self.ctx.meta.canvas_to_screen_pos(Pos2::new(0., y)).y;
+            self.ctx.painter.hline(rect.x_range(), y_screen, stroke);
+            y += cell;
+        }
+    }
+
+    fn draw_selection_hull(&self) {
+        if !self.ctx.style.draw_selection_hull {
+            return;
+        }
+
+        let circles = self
+            .g
+            .selected_nodes()
+            .iter()
+            .filter_map(|idx| self.g.node(*idx))
+            .map(|n| {
+                let radius = node_size(n, Vec2::new(0., 1.)) + HULL_PADDING;
+                (
+                    self.ctx.meta.canvas_to_screen_pos(n.location()),
+                    self.ctx.meta.canvas_to_screen_size(radius),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let style = self.ctx.ctx.style();
+        let selection = style.visuals.selection;
+        let color_selected = self.ctx.style.color_selected;
+        let fill = color_selected
+            .unwrap_or(selection.bg_fill)
+            .gamma_multiply(0.25);
+        let stroke = Stroke::new(1., color_selected.unwrap_or(selection.stroke.color));
+        if let Some(hull) = hull_shape(&circles, fill, stroke) {
+            self.ctx.painter.add(hull);
+        }
+    }
+
+    /// Draws the edge being created from its source node to the pointer.
+    fn draw_edge_draft(&self) {
+        let Some((source, end)) = self.ctx.meta.edge_draft() else {
+            return;
+        };
+        let Some(n) = self.g.node(NodeIndex::new(source)) else {
+            return;
+        };
+
+        let start = self
+            .ctx
+            .meta
+            .rendered_location(source)
+            .unwrap_or_else(|| n.location());
+        let style = self.ctx.ctx.style();
+        self.ctx.painter.add(Shape::line_segment(
+            [
+                self.ctx.meta.canvas_to_screen_pos(start),
+                self.ctx.meta.canvas_to_screen_pos(end),
+            ],
+            style.visuals.selection.stroke,
+        ));
+    }
+
+    fn draw_selection_box(&self) {
+        let Some(rect) = self.ctx.meta.selection_box() else {
+            return;
+        };
+
+        let style = self.ctx.ctx.style();
+        let selection_box = self.ctx.style.selection_box;
+        let selection = &style.visuals.selection;
+        let fill = selection_box.color.unwrap_or(selection.bg_fill);
+        let stroke = Stroke::new(
+            selection_box.stroke_width,
+            selection_box.color.unwrap_or(selection.stroke.color),
+        );
+        self.draw_box(
+            rect,
+            fill.gamma_multiply(selection_box.fill_alpha),
+            stroke,
+            selection_box.dashed,
+        );
+    }
+
+    /// Zoom box is drawn without fill to tell it apart from the selection box.
+    fn draw_zoom_box(&self) {
+        let Some(rect) = self.ctx.meta.zoom_box() else {
+            return;
+        };
+
+        let style = self.ctx.ctx.style();
+        let stroke = Stroke::new(1., style.visuals.selection.stroke.color);
+        self.draw_box(rect, Color32::TRANSPARENT, stroke, false);
+    }
+
+    /// Draws a box given in canvas coordinates.
+    fn draw_box(&self, rect: Rect, fill: Color32, stroke: Stroke, dashed: bool) {
+        let rect = Rect::from_two_pos(
+            self.ctx.meta.canvas_to_screen_pos(rect.min),
+            self.ctx.meta.canvas_to_screen_pos(rect.max),
+        );
+        self.ctx
+            .painter
+            .extend(box_shapes(rect, fill, stroke, dashed));
+    }
+
+    fn draw_annotations(&self) {
+        for a in self.annotations {
+            self.ctx.painter.add(a.shape(self.ctx));
+        }
+    }
+
+    fn draw_legend(&self) {
+        let Some(legend) = &self.ctx.style.legend else {
+            return;
+        };
+
+        for s in legend.shapes(self.ctx) {
+            self.ctx.painter.add(s);
+        }
+    }
+
+    fn draw_minimap(&self) {
+        let Some(minimap) = &self.minimap else {
+            return;
+        };
+
+        let visuals = self.ctx.ctx.style().visuals.clone();
+        for s in minimap.shapes(self.g, self.ctx.meta, &visuals) {
+            self.ctx.painter.add(s);
+        }
     }
 
     fn draw_postponed(&mut self) {
@@ -72,60 +336,497 @@ where
     }
 
     fn draw_nodes(&mut self) {
-        self.g
+        let order = self.ctx.style.draw_order;
+        let viewport = self.viewport();
+        let focus = self.hover_focus();
+        let lod = self.is_lod();
+        let visuals = self.ctx.ctx.style().visuals.clone();
+        let style = self.ctx.style;
+        // the order is kept for hit-testing, so that clicks find the node drawn on top
+        let mut nodes_shapes = self
             .g
-            .node_indices()
-            .collect::<Vec<_>>()
+            .update_nodes_order(order)
+            .to_vec()
             .into_iter()
-            .for_each(|idx| {
+            .filter_map(|idx| {
                 let n = self.g.node_mut(idx).unwrap();
-                let props = n.props().clone();
+                let mut props = n.props().clone();
+                if let Some(loc) = self.ctx.meta.rendered_location(idx.index()) {
+                    props.set_location(loc);
+                }
+                let dot = lod.then(|| {
+                    let color = if n.selected() {
+                        style
+                            .color_selected
+                            .unwrap_or(visuals.selection.stroke.color)
+                    } else {
+                        n.color()
+                            .or(style.color_node)
+                            .unwrap_or(visuals.widgets.inactive.fg_stroke.color)
+                    };
+                    let radius = node_size(n, Vec2::new(0., 1.));
+                    lod_node_shape(
+                        self.ctx.meta.canvas_to_screen_pos(props.location()),
+                        self.ctx.meta.canvas_to_screen_size(radius),
+                        color,
+                    )
+                });
 
+                // displays are updated even when culled as hit-testing relies on them
                 let display = n.display_mut();
                 display.update(&props);
-                let shapes = display.shapes(self.ctx);
-
-                if n.selected() || n.dragged() {
-                    for s in shapes {
-                        self.delayed.push(s);
-                    }
-                } else {
-                    for s in shapes {
-                        self.ctx.painter.add(s);
+                if let Some(viewport) = viewport {
+                    let bounds = Graph::<N, E, Ty, Ix, Nd, Ed>::display_bounds(display);
+                    if !viewport.intersects(self.ctx.meta.canvas_to_screen_rect(bounds)) {
+                        return None;
                     }
                 }
-            });
+                let mut shapes = match dot {
+                    Some(dot) => vec![dot],
+                    None => display.shapes(self.ctx),
+                };
+                if focus.as_ref().is_some_and(|(_, near)| !near.contains(&idx)) {
+                    fade(&mut shapes, self.ctx.style.fade_opacity);
+                }
+
+                // interacted nodes are drawn on top of the selected edges as well
+                let postponed = order == DrawOrder::SelectedLast && (n.selected() || n.dragged());
+                Some((idx, shapes, postponed))
+            })
+            .collect::<Vec<_>>();
+
+        if self.ctx.style.avoid_label_overlap {
+            self.hide_overlapping_labels(&mut nodes_shapes);
+        }
+
+        for (_, shapes, postponed) in nodes_shapes {
+            if postponed {
+                self.delayed.extend(shapes);
+            } else {
+                for s in shapes {
+                    self.ctx.painter.add(s);
+                }
+            }
+        }
+
+        // displays are in sync with the drawn nodes now
+        self.g.update_nodes_index();
+    }
+
+    /// Hides labels of nodes overlapping labels of nodes with higher priority. Interacted nodes
+    /// have the highest priority followed by nodes with more incident edges.
+    fn hide_overlapping_labels(&self, nodes_shapes: &mut [(NodeIndex<Ix>, Vec<Shape>, bool)]) {
+        let mut by_priority = (0..nodes_shapes.len()).collect::<Vec<_>>();
+        by_priority.sort_by_key(|i| {
+            let idx = nodes_shapes[*i].0;
+            let interacted = self
+                .g
+                .node(idx)
+                .is_some_and(|n| n.selected() || n.dragged());
+            (
+                Reverse(interacted),
+                Reverse(self.g.incident_edges(idx).count()),
+            )
+        });
+
+        let mut taken = vec![];
+        for i in by_priority {
+            drop_overlapping_labels(&mut nodes_shapes[i].1, &mut taken);
+        }
     }
 
     fn draw_edges(&mut self) {
-        self.g
-            .g
-            .edge_indices()
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|idx| {
-                let (idx_start, idx_end) = self.g.edge_endpoints(idx).unwrap();
+        let viewport = self.viewport();
+        let focus = self.hover_focus();
+        let lod = self.is_lod();
+        let visuals = self.ctx.ctx.style().visuals.clone();
+        let style = self.ctx.style;
+        for idx in self.g.g.edge_indices().collect::<Vec<_>>() {
+            let (idx_start, idx_end) = self.g.edge_endpoints(idx).unwrap();
 
-                // FIXME: not a good decision to clone nodes for every edge
-                let start = self.g.node(idx_start).cloned().unwrap();
-                let end = self.g.node(idx_end).cloned().unwrap();
+            if let Some(viewport) = viewport {
+                let start = self.edge_end_screen_rect(idx_start);
+                let end = self.edge_end_screen_rect(idx_end);
+                if is_edge_culled(viewport, start, end) {
+                    let e = self.g.edge_mut(idx).unwrap();
+                    let props = e.props().clone();
+                    e.display_mut().update(&props);
+                    continue;
+                }
+            }
 
-                let e = self.g.edge_mut(idx).unwrap();
-                let props = e.props().clone();
+            // FIXME: not a good decision to clone nodes for every edge
+            let mut start = self.g.node(idx_start).cloned().unwrap();
+            let mut end = self.g.node(idx_end).cloned().unwrap();
+            for n in [&mut start, &mut end] {
+                if let Some(loc) = self.ctx.meta.rendered_location(n.id().index()) {
+                    n.set_location(loc);
+                }
+            }
 
-                let display = e.display_mut();
-                display.update(&props);
-                let shapes = display.shapes(&start, &end, self.ctx);
+            let e = self.g.edge_mut(idx).unwrap();
+            let props = e.props().clone();
 
-                if e.selected() {
-                    for s in shapes {
-                        self.delayed.push(s);
-                    }
+            let display = e.display_mut();
+            display.update(&props);
+            let mut shapes = if !lod {
+                display.shapes(&start, &end, self.ctx)
+            } else if idx_start == idx_end {
+                // loops are smaller than the dot of their node
+                vec![]
+            } else {
+                let color = if e.selected() {
+                    style
+                        .color_selected
+                        .unwrap_or(visuals.selection.stroke.color)
                 } else {
-                    for s in shapes {
-                        self.ctx.painter.add(s);
-                    }
+                    e.color()
+                        .or(style.color_edge)
+                        .unwrap_or(visuals.widgets.inactive.fg_stroke.color)
+                };
+                vec![Shape::line_segment(
+                    [
+                        self.ctx.meta.canvas_to_screen_pos(start.location()),
+                        self.ctx.meta.canvas_to_screen_pos(end.location()),
+                    ],
+                    Stroke::new(1., color),
+                )]
+            };
+            if focus
+                .as_ref()
+                .is_some_and(|(hovered, _)| ![idx_start, idx_end].contains(hovered))
+            {
+                fade(&mut shapes, self.ctx.style.fade_opacity);
+            }
+
+            if e.selected() {
+                self.delayed.extend(shapes);
+            } else {
+                for s in shapes {
+                    self.ctx.painter.add(s);
                 }
+            }
+        }
+    }
+
+    /// Whether the graph is zoomed out below the level of detail threshold, see
+    /// [`SettingsStyle::with_lod_threshold`].
+    fn is_lod(&self) -> bool {
+        self.ctx
+            .style
+            .lod_threshold
+            .is_some_and(|threshold| self.ctx.meta.zoom < threshold)
+    }
+
+    /// Returns the hovered node and its neighborhood if the rest of the graph is faded.
+    fn hover_focus(&self) -> Option<(NodeIndex<Ix>, HashSet<NodeIndex<Ix>>)> {
+        if !self.ctx.style.hover_highlight_neighbors {
+            return None;
+        }
+
+        let hovered = self.g.hovered_node()?;
+        Some((hovered, self.g.neighborhood(hovered)))
+    }
+
+    /// Widget rect padded by [`CULL_MARGIN`] or `None` if culling is disabled.
+    fn viewport(&self) -> Option<Rect> {
+        self.ctx
+            .style
+            .cull_offscreen
+            .then(|| self.ctx.painter.clip_rect().expand(CULL_MARGIN))
+    }
+
+    /// Screen rect around the rendered location of an edge endpoint. It is padded by the node
+    /// size so that it covers loops of the node as well.
+    fn edge_end_screen_rect(&self, idx: NodeIndex<Ix>) -> Rect {
+        let n = self.g.node(idx).unwrap();
+        let loc = self
+            .ctx
+            .meta
+            .rendered_location(idx.index())
+            .unwrap_or_else(|| n.location());
+        // displays of nodes are updated after edges are drawn, so only their size is reliable
+        let size = Graph::<N, E, Ty, Ix, Nd, Ed>::display_bounds(n.display()).size();
+        self.ctx
+            .meta
+            .canvas_to_screen_rect(Rect::from_center_size(loc, size * 3.))
+    }
+}
+
+/// Dot standing in for a node below the level of detail threshold. It is never smaller than
+/// [`LOD_MIN_RADIUS`] so that nodes stay visible.
+fn lod_node_shape(center: Pos2, radius: f32, color: Color32) -> Shape {
+    Shape::circle_filled(center, radius.max(LOD_MIN_RADIUS), color)
+}
+
+/// Edges are culled when both endpoints lie beyond the same side of the `viewport`. Edges
+/// with endpoints on different sides may cross the viewport, so they are kept.
+fn is_edge_culled(viewport: Rect, start: Rect, end: Rect) -> bool {
+    outcode(viewport, start) & outcode(viewport, end) != 0
+}
+
+/// Bit mask of the sides of the `viewport` which the `rect` lies entirely beyond.
+fn outcode(viewport: Rect, rect: Rect) -> u8 {
+    let mut code = 0;
+    if rect.max.x < viewport.min.x {
+        code |= 1;
+    }
+    if rect.min.x > viewport.max.x {
+        code |= 2;
+    }
+    if rect.max.y < viewport.min.y {
+        code |= 4;
+    }
+    if rect.min.y > viewport.max.y {
+        code |= 8;
+    }
+
+    code
+}
+
+/// Returns the grid `cell` multiplied by the smallest power of two for which lines are at least
+/// [`MIN_GRID_SPACING`] apart on the screen at the `zoom`, or `None` for a degenerate grid.
+fn adaptive_grid_cell(cell: f32, zoom: f32) -> Option<f32> {
+    let spacing = cell * zoom;
+    if !spacing.is_finite() || spacing <= 0. {
+        return None;
+    }
+    if spacing >= MIN_GRID_SPACING {
+        return Some(cell);
+    }
+
+    // lines closer than the minimum would only darken the whole widget
+    Some(cell * (MIN_GRID_SPACING / spacing).log2().ceil().exp2())
+}
+
+/// Multiplies the opacity of all colors of the shapes by `opacity`.
+fn fade(shapes: &mut [Shape], opacity: f32) {
+    for s in shapes {
+        adjust_colors(s, move |c| *c = c.gamma_multiply(opacity));
+    }
+}
+
+/// Removes text shapes overlapping any of the `taken` rects together with their backgrounds
+/// and adds rects of the kept text shapes to `taken`.
+fn drop_overlapping_labels(shapes: &mut Vec<Shape>, taken: &mut Vec<Rect>) {
+    let mut hidden = vec![false; shapes.len()];
+    for (i, s) in shapes.iter().enumerate() {
+        let Shape::Text(text) = s else {
+            continue;
+        };
+
+        let rect = text.visual_bounding_rect();
+        if !taken.iter().any(|t| t.intersects(rect)) {
+            taken.push(rect);
+            continue;
+        }
+
+        hidden[i] = true;
+        // label background is added right before the text
+        if let Some(Shape::Rect(bg)) = i.checked_sub(1).and_then(|prev| shapes.get(prev)) {
+            if bg.rect.contains_rect(rect) {
+                hidden[i - 1] = true;
+            }
+        }
+    }
+
+    let mut hidden = hidden.into_iter();
+    shapes.retain(|_| !hidden.next().unwrap_or_default());
+}
+
+/// Shapes of a box given in screen coordinates.
+fn box_shapes(rect: Rect, fill: Color32, stroke: Stroke, dashed: bool) -> Vec<Shape> {
+    let mut shapes = vec![Shape::rect_filled(rect, 0., fill)];
+    if dashed {
+        let corners = [
+            rect.left_top(),
+            rect.right_top(),
+            rect.right_bottom(),
+            rect.left_bottom(),
+            rect.left_top(),
+        ];
+        shapes.extend(Shape::dashed_line(&corners, stroke, 4., 3.));
+    } else {
+        shapes.push(Shape::rect_stroke(rect, 0., stroke));
+    }
+    shapes
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{epaint::ColorMode, FontId, LayerId, RawInput, Rounding};
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+    use crate::layouts::random;
+
+    fn label(ctx: &Context, pos: Pos2) -> Shape {
+        let galley =
+            ctx.fonts(|f| f.layout_no_wrap("label".to_string(), FontId::default(), Color32::WHITE));
+        TextShape::new(pos, galley, Color32::WHITE).into()
+    }
+
+    #[test]
+    fn test_is_edge_culled() {
+        let viewport = Rect::from_min_max(Pos2::ZERO, Pos2::new(100., 100.));
+        let at = |x: f32, y: f32| Rect::from_center_size(Pos2::new(x, y), Vec2::splat(10.));
+
+        assert!(!is_edge_culled(viewport, at(50., 50.), at(500., 50.)));
+        assert!(is_edge_culled(viewport, at(-50., 0.), at(-50., 100.)));
+        assert!(is_edge_culled(viewport, at(500., 500.), at(200., 300.)));
+        // both endpoints are outside but the edge crosses the viewport
+        assert!(!is_edge_culled(viewport, at(-50., 50.), at(150., 50.)));
+        assert!(!is_edge_culled(viewport, at(-50., 50.), at(50., -50.)));
+    }
+
+    #[test]
+    fn test_culling_of_large_graph() {
+        // 100x100 grid with spacing 10 zoomed in to its 10x10 corner
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let idxs = (0..100 * 100)
+            .map(|i| {
+                let loc = Pos2::new((i % 100) as f32, (i / 100) as f32) * 10.;
+                g.add_node_with_location((), loc)
+            })
+            .collect::<Vec<_>>();
+        for i in 0..100 * 100 {
+            if i % 100 < 99 {
+                g.add_edge(idxs[i], idxs[i + 1], ());
+            }
+            if i / 100 < 99 {
+                g.add_edge(idxs[i], idxs[i + 100], ());
+            }
+        }
+
+        let mut meta = Metadata::default();
+        meta.zoom = 10.;
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(1000.));
+        let mut draw = |culling: bool| {
+            let style = SettingsStyle::new().with_culling(culling);
+            let ctx = Context::default();
+            let output = ctx.run(RawInput::default(), |ctx| {
+                let painter = Painter::new(ctx.clone(), LayerId::background(), rect);
+                let draw_ctx = DrawContext {
+                    ctx,
+                    painter: &painter,
+                    style: &style,
+                    is_directed: true,
+                    meta: &meta,
+                };
+                Drawer::<_, _, _, _, _, _, random::State, random::Random>::new(&mut g, &draw_ctx)
+                    .draw();
             });
+            output.shapes.len()
+        };
+
+        let all = draw(false);
+        let culled = draw(true);
+        assert!(culled > 0);
+        assert!(culled * 50 < all, "{culled} of {all} shapes drawn");
+
+        // culled nodes are still found by position
+        let far = idxs[100 * 100 - 1];
+        let pos = meta.canvas_to_screen_pos(g.node(far).unwrap().location());
+        assert_eq!(g.node_by_screen_pos(&meta, pos), Some(far));
+    }
+
+    #[test]
+    fn test_adaptive_grid_cell() {
+        assert_eq!(adaptive_grid_cell(10., 1.), Some(10.));
+        assert_eq!(adaptive_grid_cell(10., 0.8), Some(10.));
+        assert_eq!(adaptive_grid_cell(10., 0.5), Some(20.));
+        assert_eq!(adaptive_grid_cell(10., 0.1), Some(80.));
+        assert_eq!(adaptive_grid_cell(10., 0.), None);
+        assert_eq!(adaptive_grid_cell(-10., 1.), None);
+    }
+
+    #[test]
+    fn test_lod_node_shape() {
+        let Shape::Circle(dot) = lod_node_shape(Pos2::ZERO, 0.05, Color32::WHITE) else {
+            panic!("expected a circle");
+        };
+        assert_eq!(dot.radius, LOD_MIN_RADIUS);
+        assert!(dot.stroke.is_empty());
+
+        let Shape::Circle(dot) = lod_node_shape(Pos2::ZERO, 3., Color32::WHITE) else {
+            panic!("expected a circle");
+        };
+        assert_eq!(dot.radius, 3.);
+    }
+
+    #[test]
+    fn test_fade() {
+        let mut shapes = vec![
+            Shape::circle_filled(Pos2::ZERO, 5., Color32::WHITE),
+            Shape::line_segment(
+                [Pos2::ZERO, Pos2::new(10., 0.)],
+                Stroke::new(1., Color32::RED),
+            ),
+        ];
+        fade(&mut shapes, 0.5);
+
+        let Shape::Circle(circle) = &shapes[0] else {
+            panic!("expected a circle");
+        };
+        assert_eq!(circle.fill, Color32::WHITE.gamma_multiply(0.5));
+        let Shape::LineSegment { stroke, .. } = &shapes[1] else {
+            panic!("expected a line segment");
+        };
+        assert_eq!(
+            stroke.color,
+            ColorMode::Solid(Color32::RED.gamma_multiply(0.5))
+        );
+    }
+
+    #[test]
+    fn test_drop_overlapping_labels() {
+        let ctx = Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |_| {});
+
+        let mut taken = vec![];
+        let mut first = vec![
+            Shape::circle_filled(Pos2::ZERO, 5., Color32::WHITE),
+            label(&ctx, Pos2::ZERO),
+        ];
+        drop_overlapping_labels(&mut first, &mut taken);
+        assert_eq!(first.len(), 2);
+        assert_eq!(taken.len(), 1);
+
+        let overlapping = label(&ctx, Pos2::new(5., 2.));
+        let background = Shape::rect_filled(
+            overlapping.visual_bounding_rect().expand(2.),
+            Rounding::ZERO,
+            Color32::BLACK,
+        );
+        let mut second = vec![
+            Shape::circle_filled(Pos2::new(5., 2.), 5., Color32::WHITE),
+            background,
+            overlapping,
+        ];
+        drop_overlapping_labels(&mut second, &mut taken);
+        assert_eq!(second.len(), 1);
+        assert!(matches!(second[0], Shape::Circle(_)));
+
+        let mut third = vec![label(&ctx, Pos2::new(500., 500.))];
+        drop_overlapping_labels(&mut third, &mut taken);
+        assert_eq!(third.len(), 1);
+        assert_eq!(taken.len(), 2);
+    }
+
+    #[test]
+    fn test_box_shapes() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        let stroke = Stroke::new(1., Color32::WHITE);
+
+        let solid = box_shapes(rect, Color32::TRANSPARENT, stroke, false);
+        assert_eq!(solid.len(), 2);
+        assert!(matches!(solid[1], Shape::Rect(_)));
+
+        // 400 pixels of border split into dashes of 4 and gaps of 3
+        let dashed = box_shapes(rect, Color32::TRANSPARENT, stroke, true);
+        assert!(dashed.len() > 50);
+        assert!(dashed[1..]
+            .iter()
+            .all(|s| matches!(s, Shape::LineSegment { .. })));
     }
 }
@@ -0,0 +1,121 @@
+use egui::{Color32, Pos2, Shape, Stroke, Vec2};
+
+/// Number of points approximating the padding circle around every hull point.
+const PADDING_SEGMENTS: usize = 16;
+
+/// Returns the convex hull of `points` in counter-clockwise order without collinear points.
+///
+/// Uses the monotone chain algorithm.
+pub(crate) fn convex_hull(points: &[Pos2]) -> Vec<Pos2> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross = |o: Pos2, a: Pos2, b: Pos2| (a - o).x * (b - o).y - (a - o).y * (b - o).x;
+    let reversed = points.iter().rev().copied().collect::<Vec<_>>();
+    let mut hull: Vec<Pos2> = Vec::with_capacity(2 * points.len());
+    for chain in [&points, &reversed] {
+        let start = hull.len();
+        for p in chain {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], *p) <= 0.
+            {
+                hull.pop();
+            }
+            hull.push(*p);
+        }
+        // last point of each chain is the first point of the other one
+        hull.pop();
+    }
+
+    hull
+}
+
+/// Builds a translucent padded hull enclosing circles given by centers and radii.
+///
+/// Degenerate cases are handled naturally: a single circle yields a circle and two circles
+/// yield a capsule.
+pub(crate) fn hull_shape(circles: &[(Pos2, f32)], fill: Color32, stroke: Stroke) -> Option<Shape> {
+    if circles.is_empty() {
+        return None;
+    }
+
+    let points = circles
+        .iter()
+        .flat_map(|(center, radius)| {
+            (0..PADDING_SEGMENTS).map(move |i| {
+                let angle = std::f32::consts::TAU * i as f32 / PADDING_SEGMENTS as f32;
+                *center + Vec2::angled(angle) * *radius
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Some(Shape::convex_polygon(convex_hull(&points), fill, stroke))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            Pos2::new(0., 0.),
+            Pos2::new(10., 0.),
+            Pos2::new(5., 5.),
+            Pos2::new(10., 10.),
+            Pos2::new(0., 10.),
+            Pos2::new(5., 0.),
+            Pos2::new(0., 0.),
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            vec![
+                Pos2::new(0., 0.),
+                Pos2::new(10., 0.),
+                Pos2::new(10., 10.),
+                Pos2::new(0., 10.),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convex_hull_degenerate() {
+        assert!(convex_hull(&[]).is_empty());
+        assert_eq!(
+            convex_hull(&[Pos2::new(1., 1.), Pos2::new(1., 1.)]),
+            vec![Pos2::new(1., 1.)]
+        );
+        assert_eq!(
+            convex_hull(&[Pos2::new(0., 0.), Pos2::new(5., 5.), Pos2::new(10., 10.)]),
+            vec![Pos2::new(0., 0.), Pos2::new(10., 10.)]
+        );
+    }
+
+    #[test]
+    fn test_hull_shape_degenerate() {
+        assert!(hull_shape(&[], Color32::WHITE, Stroke::NONE).is_none());
+
+        let Some(Shape::Path(circle)) =
+            hull_shape(&[(Pos2::ZERO, 10.)], Color32::WHITE, Stroke::NONE)
+        else {
+            panic!("invalid shape type")
+        };
+        assert_eq!(circle.points.len(), PADDING_SEGMENTS);
+
+        let Some(Shape::Path(capsule)) = hull_shape(
+            &[(Pos2::ZERO, 10.), (Pos2::new(100., 0.), 10.)],
+            Color32::WHITE,
+            Stroke::NONE,
+        ) else {
+            panic!("invalid shape type")
+        };
+        assert!(capsule
+            .points
+            .iter()
+            .all(|p| p.x >= -10. - 1e-3 && p.x <= 110. + 1e-3 && p.y.abs() <= 10. + 1e-3));
+    }
+}
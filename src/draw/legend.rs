@@ -0,0 +1,164 @@
+use egui::{
+    epaint::TextShape, Color32, Pos2, Rect, Rounding, Shape, Stroke, TextStyle, Vec2, Visuals,
+};
+
+use crate::{draw::DrawContext, settings::Corner};
+
+pub(crate) const PADDING: f32 = 6.;
+const SPACING: f32 = 4.;
+pub(crate) const MARGIN: f32 = 8.;
+const ROUNDING: f32 = 4.;
+
+/// Shape of the marker displayed next to a [`Legend`] entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LegendShape {
+    #[default]
+    Circle,
+    Square,
+    Triangle,
+}
+
+#[derive(Debug, Clone)]
+struct LegendEntry {
+    label: String,
+    color: Color32,
+    shape: LegendShape,
+}
+
+/// Legend explaining the meaning of colors and shapes used to draw the graph.
+///
+/// The legend is drawn in screen space in one of the widget corners on top of the graph.
+/// It is purely decorative and does not intercept any interactions with the graph.
+///
+/// ```
+/// use egui::Color32;
+/// use egui_graphs::{Corner, Legend, LegendShape};
+///
+/// let legend = Legend::new()
+///     .with_entry("service", Color32::LIGHT_BLUE, LegendShape::Circle)
+///     .with_entry("database", Color32::LIGHT_GREEN, LegendShape::Square)
+///     .with_corner(Corner::BottomLeft);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Legend {
+    entries: Vec<LegendEntry>,
+    corner: Corner,
+    visible: bool,
+}
+
+impl Default for Legend {
+    fn default() -> Self {
+        Self {
+            entries: Vec::default(),
+            corner: Corner::default(),
+            visible: true,
+        }
+    }
+}
+
+impl Legend {
+    /// Creates new empty [`Legend`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an entry to the legend. Entries are displayed in the order they were added.
+    pub fn with_entry(
+        mut self,
+        label: impl Into<String>,
+        color: Color32,
+        shape: LegendShape,
+    ) -> Self {
+        self.entries.push(LegendEntry {
+            label: label.into(),
+            color,
+            shape,
+        });
+        self
+    }
+
+    /// Corner of the widget where the legend is placed.
+    ///
+    /// Default: [`Corner::TopRight`]
+    pub fn with_corner(mut self, corner: Corner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /// Shows or hides the legend without the need to rebuild it.
+    ///
+    /// Default: `true`
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub(crate) fn shapes(&self, ctx: &DrawContext) -> Vec<Shape> {
+        if !self.visible || self.entries.is_empty() {
+            return vec![];
+        }
+
+        let style = ctx.ctx.style();
+        let font = TextStyle::Body.resolve(&style);
+        let text_color = style.visuals.text_color();
+
+        let galleys = self
+            .entries
+            .iter()
+            .map(|e| {
+                ctx.ctx
+                    .fonts(|f| f.layout_no_wrap(e.label.clone(), font.clone(), text_color))
+            })
+            .collect::<Vec<_>>();
+
+        let row_height = galleys.iter().map(|g| g.size().y).fold(0., f32::max);
+        let text_width = galleys.iter().map(|g| g.size().x).fold(0., f32::max);
+        let rows = self.entries.len() as f32;
+        let size = Vec2::new(
+            2. * PADDING + row_height + SPACING + text_width,
+            2. * PADDING + rows * row_height + (rows - 1.) * SPACING,
+        );
+        let frame = self.corner.place(ctx.painter.clip_rect(), size, MARGIN);
+
+        let mut res = Vec::with_capacity(2 + 2 * self.entries.len());
+        res.extend(panel_shapes(frame, &style.visuals));
+
+        for (i, (entry, galley)) in self.entries.iter().zip(galleys).enumerate() {
+            let top = frame.top() + PADDING + i as f32 * (row_height + SPACING);
+            let marker = Rect::from_min_size(
+                Pos2::new(frame.left() + PADDING, top),
+                Vec2::splat(row_height),
+            );
+            res.push(marker_shape(
+                entry.shape,
+                marker.shrink(row_height / 6.),
+                entry.color,
+            ));
+
+            let text_pos = Pos2::new(marker.right() + SPACING, top);
+            res.push(TextShape::new(text_pos, galley, text_color).into());
+        }
+
+        res
+    }
+}
+
+/// Background of an overlay panel drawn on top of the graph.
+pub(crate) fn panel_shapes(frame: Rect, visuals: &Visuals) -> [Shape; 2] {
+    [
+        Shape::rect_filled(frame, Rounding::same(ROUNDING), visuals.window_fill),
+        Shape::rect_stroke(frame, Rounding::same(ROUNDING), visuals.window_stroke),
+    ]
+}
+
+fn marker_shape(shape: LegendShape, rect: Rect, color: Color32) -> Shape {
+    match shape {
+        LegendShape::Circle => Shape::circle_filled(rect.center(), rect.width() / 2., color),
+        LegendShape::Square => Shape::rect_filled(rect, Rounding::ZERO, color),
+        LegendShape::Triangle => Shape::convex_polygon(
+            vec![rect.center_top(), rect.right_bottom(), rect.left_bottom()],
+            color,
+            Stroke::NONE,
+        ),
+    }
+}
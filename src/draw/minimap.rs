@@ -0,0 +1,133 @@
+use egui::{Pos2, Rect, Rounding, Shape, Vec2, Visuals};
+use petgraph::{stable_graph::IndexType, EdgeType};
+
+use crate::{
+    draw::{
+        legend::{panel_shapes, MARGIN, PADDING},
+        DisplayEdge, DisplayNode,
+    },
+    node_size,
+    settings::Corner,
+    Graph, Metadata,
+};
+
+/// Minimum radius of nodes in the minimap in screen points, so that small nodes stay visible.
+const MIN_NODE_RADIUS: f32 = 1.5;
+
+/// Scaled down view of the whole graph drawn in a corner of the widget.
+///
+/// Maps canvas coordinates to the screen inside of the minimap frame and back.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Minimap {
+    frame: Rect,
+    bounds: Rect,
+    scale: f32,
+}
+
+impl Minimap {
+    /// Places the minimap of the given `size` in the `corner` of the `widget` rect and fits the
+    /// graph `bounds` in canvas coordinates into it.
+    pub(crate) fn new(widget: Rect, corner: Corner, size: Vec2, bounds: Rect) -> Self {
+        let frame = corner.place(widget, size, MARGIN);
+        let inner = frame.shrink(PADDING);
+        // a single node has empty bounds
+        let extent = bounds.size().max(Vec2::splat(1.));
+        let scale = (inner.width() / extent.x).min(inner.height() / extent.y);
+
+        Self {
+            frame,
+            bounds,
+            scale,
+        }
+    }
+
+    pub(crate) fn frame(&self) -> Rect {
+        self.frame
+    }
+
+    pub(crate) fn canvas_to_minimap_pos(&self, pos: Pos2) -> Pos2 {
+        self.frame.center() + (pos - self.bounds.center()) * self.scale
+    }
+
+    /// This is the inverse of [`Minimap::canvas_to_minimap_pos`].
+    pub(crate) fn minimap_to_canvas_pos(&self, pos: Pos2) -> Pos2 {
+        self.bounds.center() + (pos - self.frame.center()) / self.scale
+    }
+
+    /// Returns the part of the graph visible in the widget as a rect in the minimap.
+    pub(crate) fn viewport(&self, meta: &Metadata) -> Rect {
+        Rect::from_two_pos(
+            self.canvas_to_minimap_pos(meta.screen_to_canvas_pos(meta.rect.min)),
+            self.canvas_to_minimap_pos(meta.screen_to_canvas_pos(meta.rect.max)),
+        )
+    }
+
+    pub(crate) fn shapes<N, E, Ty, Ix, Dn, De>(
+        &self,
+        g: &Graph<N, E, Ty, Ix, Dn, De>,
+        meta: &Metadata,
+        visuals: &Visuals,
+    ) -> Vec<Shape>
+    where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        let mut res = Vec::with_capacity(3 + g.node_count());
+        res.extend(panel_shapes(self.frame, visuals));
+
+        for (_, n) in g.nodes_iter() {
+            let color = if n.selected() {
+                visuals.selection.stroke.color
+            } else {
+                n.color().unwrap_or(visuals.text_color())
+            };
+            let radius = (node_size(n, Vec2::new(0., 1.)) * self.scale).max(MIN_NODE_RADIUS);
+            res.push(Shape::circle_filled(
+                self.canvas_to_minimap_pos(n.location()),
+                radius,
+                color,
+            ));
+        }
+
+        // the view may show more than the graph, the frame cuts it off
+        let viewport = self.viewport(meta).intersect(self.frame);
+        if viewport.is_positive() {
+            res.push(Shape::rect_stroke(
+                viewport,
+                Rounding::ZERO,
+                visuals.selection.stroke,
+            ));
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_fit_frame() {
+        let widget = Rect::from_min_size(Pos2::ZERO, Vec2::new(800., 600.));
+        let bounds = Rect::from_min_max(Pos2::new(-500., -100.), Pos2::new(1500., 100.));
+        let minimap = Minimap::new(widget, Corner::BottomRight, Vec2::new(200., 150.), bounds);
+
+        let frame = minimap.frame();
+        assert_eq!(frame.max, Pos2::new(800. - MARGIN, 600. - MARGIN));
+        assert!(frame.contains(minimap.canvas_to_minimap_pos(bounds.min)));
+        assert!(frame.contains(minimap.canvas_to_minimap_pos(bounds.max)));
+        assert_eq!(
+            minimap.canvas_to_minimap_pos(bounds.center()),
+            frame.center()
+        );
+
+        let pos = Pos2::new(700., 20.);
+        let round_trip = minimap.minimap_to_canvas_pos(minimap.canvas_to_minimap_pos(pos));
+        assert!((round_trip - pos).length() < 1e-3);
+    }
+}
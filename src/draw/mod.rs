@@ -1,8 +1,15 @@
+mod annotation;
 mod displays;
 mod displays_default;
 mod drawer;
+mod hull;
+mod legend;
+mod minimap;
 
+pub use annotation::Annotation;
 pub use displays::{DisplayEdge, DisplayNode};
 pub use displays_default::DefaultEdgeShape;
 pub use displays_default::DefaultNodeShape;
 pub use drawer::{DrawContext, Drawer};
+pub use legend::{Legend, LegendShape};
+pub(crate) use minimap::Minimap;
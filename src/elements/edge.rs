@@ -1,5 +1,6 @@
 use std::marker::PhantomData;
 
+use egui::Color32;
 use petgraph::{
     stable_graph::{DefaultIx, EdgeIndex, IndexType},
     Directed, EdgeType,
@@ -8,13 +9,39 @@ use serde::{Deserialize, Serialize};
 
 use crate::{DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode};
 
+/// Stroke pattern of the body of an edge drawn by [`DefaultEdgeShape`].
+///
+/// Dashes and gaps are sized in canvas units, so the pattern scales with the zoom.
+/// Tips are always solid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EdgeStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
 /// Stores properties of an [Edge]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EdgeProps<E: Clone> {
     pub payload: E,
     pub order: usize,
     pub selected: bool,
+    pub highlighted: bool,
+    #[serde(default)]
+    pub hovered: bool,
     pub label: String,
+    /// Uniform color of the edge overriding the style.
+    pub color: Option<Color32>,
+    /// Width of the edge in canvas units, e.g. to encode the edge weight.
+    pub width: Option<f32>,
+    /// Stroke pattern of the edge body.
+    pub style: EdgeStyle,
+
+    /// Maps position along the edge, from `0.0` at the source to `1.0` at the target,
+    /// and the edge payload to the color of the edge at that position.
+    #[serde(skip, default = "Option::default")]
+    pub color_fn: Option<fn(f32, &E) -> Color32>,
 }
 
 /// Stores properties of an edge that can be changed. Used to apply changes to the graph.
@@ -50,7 +77,13 @@ impl<
 
             order: usize::default(),
             selected: bool::default(),
+            highlighted: bool::default(),
+            hovered: bool::default(),
             label: String::default(),
+            color: Option::default(),
+            width: Option::default(),
+            style: EdgeStyle::default(),
+            color_fn: Option::default(),
         };
 
         let display = D::from(props.clone());
@@ -108,6 +141,25 @@ impl<
         self.props.selected
     }
 
+    /// Marks the edge as incident to a selected node.
+    pub fn set_highlighted(&mut self, highlighted: bool) {
+        self.props.highlighted = highlighted;
+    }
+
+    pub fn highlighted(&self) -> bool {
+        self.props.highlighted
+    }
+
+    /// Marks the edge as the one under the pointer,
+    /// see [`crate::SettingsInteraction::with_edge_hover_enabled`].
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.props.hovered = hovered;
+    }
+
+    pub fn hovered(&self) -> bool {
+        self.props.hovered
+    }
+
     pub fn set_label(&mut self, label: String) {
         self.props.label = label;
     }
@@ -120,4 +172,58 @@ impl<
     pub fn label(&self) -> String {
         self.props.label.clone()
     }
+
+    pub fn color(&self) -> Option<Color32> {
+        self.props.color
+    }
+
+    pub fn set_color(&mut self, color: Color32) {
+        self.props.color = Some(color);
+    }
+
+    pub fn with_color(mut self, color: Color32) -> Self {
+        self.props.color = Some(color);
+        self
+    }
+
+    pub fn width(&self) -> Option<f32> {
+        self.props.width
+    }
+
+    /// Sets the width of the edge in canvas units. On the screen it scales with the zoom
+    /// but is never thinner than one pixel.
+    pub fn set_width(&mut self, width: f32) {
+        self.props.width = Some(width);
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.props.width = Some(width);
+        self
+    }
+
+    pub fn style(&self) -> EdgeStyle {
+        self.props.style
+    }
+
+    pub fn set_style(&mut self, style: EdgeStyle) {
+        self.props.style = style;
+    }
+
+    /// Draws the edge body solid, dashed or dotted, e.g. to tell inferred relationships from
+    /// explicit ones.
+    pub fn with_style(mut self, style: EdgeStyle) -> Self {
+        self.props.style = style;
+        self
+    }
+
+    /// Colors the edge along its length with the given function instead of a uniform color.
+    ///
+    /// See [`EdgeProps::color_fn`].
+    pub fn set_color_fn(&mut self, color_fn: Option<fn(f32, &E) -> Color32>) {
+        self.props.color_fn = color_fn;
+    }
+
+    pub fn color_fn(&self) -> Option<fn(f32, &E) -> Color32> {
+        self.props.color_fn
+    }
 }
@@ -1,5 +1,5 @@
 mod edge;
 mod node;
 
-pub use edge::{Edge, EdgeProps};
-pub use node::{Node, NodeProps};
+pub use edge::{Edge, EdgeProps, EdgeStyle};
+pub use node::{Node, NodeProps, NodeShape};
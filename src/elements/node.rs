@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
-use egui::{Color32, Pos2};
+use egui::{Color32, Pos2, TextureId};
 use petgraph::{
     stable_graph::{DefaultIx, IndexType, NodeIndex},
     Directed, EdgeType,
@@ -10,6 +10,19 @@ use serde::{Deserialize, Serialize};
 
 use crate::{DefaultNodeShape, DisplayNode};
 
+/// Outline of a node drawn by [`DefaultNodeShape`].
+///
+/// Squares and triangles are inscribed in the circle of the node radius, so the radius bounds
+/// the node for all shapes. Hit testing and edge endpoints follow the actual outline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeShape {
+    #[default]
+    Circle,
+    Square,
+    /// Triangle pointing up.
+    Triangle,
+}
+
 /// Stores properties of a [Node]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NodeProps<N>
@@ -20,8 +33,20 @@ where
     pub label: String,
     pub selected: bool,
     pub dragged: bool,
+    pub hovered: bool,
 
     color: Option<Color32>,
+    glow: Option<f32>,
+    #[serde(default)]
+    shape: NodeShape,
+    #[serde(default)]
+    z: i32,
+    #[serde(default)]
+    radius: Option<f32>,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(skip)]
+    texture: Option<TextureId>,
     location: Pos2,
     location_user: Option<Pos2>,
 }
@@ -37,6 +62,34 @@ where
     pub fn color(&self) -> Option<Color32> {
         self.color
     }
+
+    pub fn glow(&self) -> Option<f32> {
+        self.glow
+    }
+
+    pub fn shape(&self) -> NodeShape {
+        self.shape
+    }
+
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    pub fn radius(&self) -> Option<f32> {
+        self.radius
+    }
+
+    pub fn pinned(&self) -> bool {
+        self.pinned
+    }
+
+    pub fn texture(&self) -> Option<TextureId> {
+        self.texture
+    }
+
+    pub(crate) fn set_location(&mut self, loc: Pos2) {
+        self.location_user = Some(loc);
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -103,10 +156,17 @@ where
             payload,
             location: Pos2::default(),
             color: Option::default(),
+            glow: Option::default(),
+            shape: NodeShape::default(),
+            z: i32::default(),
+            radius: Option::default(),
+            pinned: bool::default(),
+            texture: Option::default(),
             location_user: Option::default(),
             label: String::default(),
             selected: bool::default(),
             dragged: bool::default(),
+            hovered: bool::default(),
         };
 
         Node::new_with_props(props)
@@ -157,10 +217,117 @@ where
         self.props.color()
     }
 
+    /// Sets the fill color of the node, e.g. to color nodes by category.
+    ///
+    /// Interaction state like selection is drawn as a ring around the node,
+    /// so the fill color stays visible while the node is interacted with.
     pub fn set_color(&mut self, color: Color32) {
         self.props.color = Some(color);
     }
 
+    pub fn with_color(mut self, color: Color32) -> Self {
+        self.props.color = Some(color);
+        self
+    }
+
+    pub fn glow(&self) -> Option<f32> {
+        self.props.glow()
+    }
+
+    /// Sets the intensity of the halo drawn around the node, expected in the `0.0..=1.0` range.
+    ///
+    /// Halos are drawn only if enabled with [`crate::SettingsStyle::with_glow`].
+    /// Intensity can be derived from node importance, e.g. with [`crate::degree_centrality`].
+    pub fn set_glow(&mut self, glow: Option<f32>) {
+        self.props.glow = glow;
+    }
+
+    pub fn shape(&self) -> NodeShape {
+        self.props.shape()
+    }
+
+    pub fn set_shape(&mut self, shape: NodeShape) {
+        self.props.shape = shape;
+    }
+
+    pub fn with_shape(mut self, shape: NodeShape) -> Self {
+        self.props.shape = shape;
+        self
+    }
+
+    pub fn z(&self) -> i32 {
+        self.props.z()
+    }
+
+    /// Sets the stacking order of the node. Nodes with a higher `z` are drawn on top of
+    /// nodes with a lower one and are hit first by the pointer.
+    ///
+    /// See [`crate::DrawOrder`] for how `z` combines with interactions.
+    pub fn set_z(&mut self, z: i32) {
+        self.props.z = z;
+    }
+
+    pub fn with_z(mut self, z: i32) -> Self {
+        self.props.z = z;
+        self
+    }
+
+    pub fn radius(&self) -> Option<f32> {
+        self.props.radius()
+    }
+
+    /// Sets the radius of the node in canvas units, e.g. to encode a data attribute as the
+    /// node size. On the screen it scales with the zoom like the node location.
+    ///
+    /// The radius is stored in the node properties and applied to the display right away
+    /// and on every frame, so hit testing and bounds use it before the node is first drawn.
+    /// It is kept between frames and serialized with the node. Nodes without a radius are
+    /// drawn with the default radius of the display, which can be changed with
+    /// [`Node::display_mut`].
+    pub fn set_radius(&mut self, radius: f32) {
+        self.props.radius = Some(radius);
+        self.display.update(&self.props);
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.set_radius(radius);
+        self
+    }
+
+    pub fn pinned(&self) -> bool {
+        self.props.pinned()
+    }
+
+    /// Pins the node to its location. Pinned nodes are not moved by layouts and ignore drags,
+    /// e.g. to anchor reference nodes while a force layout settles around them.
+    ///
+    /// [`Node::set_location`] still moves a pinned node.
+    pub fn set_pinned(&mut self, pinned: bool) {
+        self.props.pinned = pinned;
+    }
+
+    pub fn with_pinned(mut self, pinned: bool) -> Self {
+        self.props.pinned = pinned;
+        self
+    }
+
+    pub fn texture(&self) -> Option<TextureId> {
+        self.props.texture()
+    }
+
+    /// Sets the image drawn inside the node, e.g. an icon.
+    ///
+    /// The texture has to be already loaded by the caller, e.g. with
+    /// [`egui::Context::load_texture`]. It is not serialized with the node.
+    pub fn set_texture(&mut self, texture: Option<TextureId>) {
+        self.props.texture = texture;
+    }
+
+    pub fn with_texture(mut self, texture: TextureId) -> Self {
+        self.props.texture = Some(texture);
+        self
+    }
+
     pub fn location(&self) -> Pos2 {
         self.props.location()
     }
@@ -171,6 +338,9 @@ where
 
     // TODO: why crate? how to use by external layoyuts?? do we need this func???
     pub(crate) fn set_layout_location(&mut self, loc: Pos2) {
+        if self.props.pinned {
+            return;
+        }
         self.props.location = loc;
     }
 
@@ -190,6 +360,14 @@ where
         self.props.dragged = dragged;
     }
 
+    pub fn hovered(&self) -> bool {
+        self.props.hovered
+    }
+
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.props.hovered = hovered;
+    }
+
     pub fn label(&self) -> String {
         self.props.label.clone()
     }
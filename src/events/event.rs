@@ -1,5 +1,11 @@
+use petgraph::{
+    stable_graph::{EdgeIndex, IndexType, NodeIndex},
+    EdgeType,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::{DisplayEdge, DisplayNode, Graph};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PayloadPan {
     pub diff: [f32; 2],
@@ -12,6 +18,15 @@ pub struct PayloadZoom {
     pub new_zoom: f32,
 }
 
+/// Published when the graph was fitted to the screen. With
+/// [`crate::SettingsNavigation::with_fit_to_screen_enabled`] it is published every frame,
+/// otherwise only once when the graph is initially framed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadFitToScreen {
+    pub new_zoom: f32,
+    pub new_pan: [f32; 2],
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PayloadNodeMove {
     pub id: usize,
@@ -29,6 +44,16 @@ pub struct PayloadNodeDragEnd {
     pub id: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeHoverStart {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeHoverEnd {
+    pub id: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PayloadNodeSelect {
     pub id: usize,
@@ -49,11 +74,33 @@ pub struct PayloadNodeDoubleClick {
     pub id: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeSecondaryClick {
+    pub id: usize,
+}
+
+/// Requests removal of the node, see [`crate::SettingsInteraction::with_deletion_enabled`].
+/// Edges incident to the node are removed with it and are not requested separately.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadNodeDelete {
+    pub id: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PayloadEdgeClick {
     pub id: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeHoverStart {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeHoverEnd {
+    pub id: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PayloadEdgeSelect {
     pub id: usize,
@@ -64,18 +111,154 @@ pub struct PayloadEdgeDeselect {
     pub id: usize,
 }
 
+/// Requests removal of the edge, see [`crate::SettingsInteraction::with_deletion_enabled`].
+/// Endpoints allow to add the edge back.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeDelete {
+    pub id: usize,
+    pub source: usize,
+    pub target: usize,
+}
+
+/// Requests an edge from the `source` to the `target` node,
+/// see [`crate::SettingsInteraction::with_edge_creation_enabled`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PayloadEdgeCreate {
+    pub source: usize,
+    pub target: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Event {
     Pan(PayloadPan),
     Zoom(PayloadZoom),
+    FitToScreen(PayloadFitToScreen),
     NodeMove(PayloadNodeMove),
     NodeDragStart(PayloadNodeDragStart),
     NodeDragEnd(PayloadNodeDragEnd),
+    NodeHoverStart(PayloadNodeHoverStart),
+    NodeHoverEnd(PayloadNodeHoverEnd),
     NodeSelect(PayloadNodeSelect),
     NodeDeselect(PayloadNodeDeselect),
     NodeClick(PayloadNodeClick),
+    NodeSecondaryClick(PayloadNodeSecondaryClick),
     NodeDoubleClick(PayloadNodeDoubleClick),
+    NodeDelete(PayloadNodeDelete),
     EdgeClick(PayloadEdgeClick),
+    EdgeHoverStart(PayloadEdgeHoverStart),
+    EdgeHoverEnd(PayloadEdgeHoverEnd),
     EdgeSelect(PayloadEdgeSelect),
     EdgeDeselect(PayloadEdgeDeselect),
+    EdgeDelete(PayloadEdgeDelete),
+    EdgeCreate(PayloadEdgeCreate),
+}
+
+impl Event {
+    /// Replays the change described by the event onto the graph, e.g. to keep a mirror of the
+    /// graph shown in the widget in sync or to persist it.
+    ///
+    /// Node moves, drags, node and edge hovers, selections and deletions are applied. Edge creation is
+    /// ignored since the payload of the new edge is up to the caller, as are events which
+    /// only change the view. Events referring to missing nodes or edges are ignored.
+    pub fn apply<N, E, Ty, Ix, Dn, De>(&self, g: &mut Graph<N, E, Ty, Ix, Dn, De>)
+    where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        match self {
+            Event::NodeMove(m) => {
+                if let Some(n) = g.node_mut(NodeIndex::new(m.id)) {
+                    n.set_location(m.new_pos.into());
+                }
+            }
+            Event::NodeDragStart(PayloadNodeDragStart { id })
+            | Event::NodeDragEnd(PayloadNodeDragEnd { id }) => {
+                if let Some(n) = g.node_mut(NodeIndex::new(*id)) {
+                    n.set_dragged(matches!(self, Event::NodeDragStart(_)));
+                }
+            }
+            Event::NodeHoverStart(PayloadNodeHoverStart { id })
+            | Event::NodeHoverEnd(PayloadNodeHoverEnd { id }) => {
+                if let Some(n) = g.node_mut(NodeIndex::new(*id)) {
+                    n.set_hovered(matches!(self, Event::NodeHoverStart(_)));
+                }
+            }
+            Event::NodeSelect(PayloadNodeSelect { id })
+            | Event::NodeDeselect(PayloadNodeDeselect { id }) => {
+                if let Some(n) = g.node_mut(NodeIndex::new(*id)) {
+                    n.set_selected(matches!(self, Event::NodeSelect(_)));
+                }
+            }
+            Event::NodeDelete(PayloadNodeDelete { id }) => {
+                g.remove_node(NodeIndex::new(*id));
+            }
+            Event::EdgeHoverStart(PayloadEdgeHoverStart { id })
+            | Event::EdgeHoverEnd(PayloadEdgeHoverEnd { id }) => {
+                if let Some(e) = g.edge_mut(EdgeIndex::new(*id)) {
+                    e.set_hovered(matches!(self, Event::EdgeHoverStart(_)));
+                }
+            }
+            Event::EdgeSelect(PayloadEdgeSelect { id })
+            | Event::EdgeDeselect(PayloadEdgeDeselect { id }) => {
+                if let Some(e) = g.edge_mut(EdgeIndex::new(*id)) {
+                    e.set_selected(matches!(self, Event::EdgeSelect(_)));
+                }
+            }
+            Event::EdgeDelete(PayloadEdgeDelete { id, .. }) => {
+                g.remove_edge(EdgeIndex::new(*id));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Pos2;
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_to_mirror() {
+        let mut mirror: Graph = Graph::new(StableGraph::default());
+        let a = mirror.add_node_with_location((), Pos2::new(0., 0.));
+        let b = mirror.add_node(());
+        let ab = mirror.add_edge(a, b, ());
+
+        Event::NodeMove(PayloadNodeMove {
+            id: a.index(),
+            diff: [3., 4.],
+            new_pos: [3., 4.],
+        })
+        .apply(&mut mirror);
+        assert_eq!(mirror.node(a).unwrap().location(), Pos2::new(3., 4.));
+
+        Event::NodeSelect(PayloadNodeSelect { id: a.index() }).apply(&mut mirror);
+        Event::NodeDragStart(PayloadNodeDragStart { id: a.index() }).apply(&mut mirror);
+        assert!(mirror.node(a).unwrap().selected());
+        assert!(mirror.node(a).unwrap().dragged());
+
+        Event::EdgeDelete(PayloadEdgeDelete {
+            id: ab.index(),
+            source: a.index(),
+            target: b.index(),
+        })
+        .apply(&mut mirror);
+        assert_eq!(mirror.edge_count(), 0);
+
+        // unknown indices are ignored
+        Event::NodeMove(PayloadNodeMove {
+            id: 42,
+            diff: [1., 1.],
+            new_pos: [1., 1.],
+        })
+        .apply(&mut mirror);
+        Event::NodeDelete(PayloadNodeDelete { id: 42 }).apply(&mut mirror);
+        assert_eq!(mirror.node_count(), 2);
+    }
 }
@@ -0,0 +1,269 @@
+use egui::{Pos2, Vec2};
+use petgraph::{stable_graph::IndexType, EdgeType};
+
+use crate::{DisplayEdge, DisplayNode, Graph};
+
+use super::{
+    Event, PayloadEdgeDeselect, PayloadEdgeSelect, PayloadNodeDeselect, PayloadNodeMove,
+    PayloadNodeSelect,
+};
+
+/// Undo and redo stacks of the changes made to the graph by the widget.
+///
+/// Feed every event published by the widget to [`History::record`]. Node moves and selection
+/// changes are recorded, other events are ignored. Everything recorded between a node drag
+/// start and its end is a single step, and moves of a node during the drag are merged, so that
+/// a drag of a group of nodes is undone at once.
+///
+/// ```
+/// use egui_graphs::events::History;
+///
+/// let mut history = History::default();
+/// // for event in events { history.record(&event); }
+/// // history.undo(&mut graph);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Vec<Event>>,
+    redo: Vec<Vec<Event>>,
+    /// Whether the last step is open for the changes of a running drag
+    dragging: bool,
+}
+
+impl History {
+    pub fn record(&mut self, event: &Event) {
+        match event {
+            Event::NodeDragStart(_) => {
+                if !self.dragging {
+                    self.dragging = true;
+                    self.undo.push(Vec::new());
+                    self.redo.clear();
+                }
+                return;
+            }
+            Event::NodeDragEnd(_) => {
+                if std::mem::take(&mut self.dragging) && self.undo.last().is_some_and(Vec::is_empty)
+                {
+                    self.undo.pop();
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        if inverse(event).is_none() {
+            return;
+        }
+
+        let step = match self.undo.last_mut() {
+            Some(step) if self.dragging => step,
+            _ => {
+                self.undo.push(vec![event.clone()]);
+                self.redo.clear();
+                return;
+            }
+        };
+        if let Event::NodeMove(m) = event {
+            let merged = step.iter_mut().find_map(|e| match e {
+                Event::NodeMove(last) if last.id == m.id => Some(last),
+                _ => None,
+            });
+            if let Some(last) = merged {
+                last.diff = (Vec2::from(last.diff) + Vec2::from(m.diff)).into();
+                last.new_pos = m.new_pos;
+                return;
+            }
+        }
+        step.push(event.clone());
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Reverts the last recorded step in the graph and returns the applied changes.
+    ///
+    /// Changes are applied to the graph directly and are not published by the widget.
+    pub fn undo<N, E, Ty, Ix, Dn, De>(
+        &mut self,
+        g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+    ) -> Option<Vec<Event>>
+    where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        let step = self.undo.pop()?;
+        let reverted = step.iter().rev().filter_map(inverse).collect::<Vec<_>>();
+        for e in &reverted {
+            e.apply(g);
+        }
+        self.redo.push(step);
+        self.dragging = false;
+
+        Some(reverted)
+    }
+
+    /// Applies the last undone step to the graph again and returns its changes.
+    pub fn redo<N, E, Ty, Ix, Dn, De>(
+        &mut self,
+        g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+    ) -> Option<Vec<Event>>
+    where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        let step = self.redo.pop()?;
+        for e in &step {
+            e.apply(g);
+        }
+        self.undo.push(step.clone());
+        self.dragging = false;
+
+        Some(step)
+    }
+}
+
+/// Returns the change reverting the event, or `None` if the event does not change the graph.
+pub fn inverse(event: &Event) -> Option<Event> {
+    match event {
+        Event::NodeMove(m) => Some(Event::NodeMove(PayloadNodeMove {
+            id: m.id,
+            diff: (-Vec2::from(m.diff)).into(),
+            new_pos: (Pos2::from(m.new_pos) - Vec2::from(m.diff)).into(),
+        })),
+        Event::NodeSelect(s) => Some(Event::NodeDeselect(PayloadNodeDeselect { id: s.id })),
+        Event::NodeDeselect(s) => Some(Event::NodeSelect(PayloadNodeSelect { id: s.id })),
+        Event::EdgeSelect(s) => Some(Event::EdgeDeselect(PayloadEdgeDeselect { id: s.id })),
+        Event::EdgeDeselect(s) => Some(Event::EdgeSelect(PayloadEdgeSelect { id: s.id })),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+    use crate::events::{PayloadNodeDragEnd, PayloadNodeDragStart};
+
+    fn node_move(id: usize, diff: [f32; 2], new_pos: [f32; 2]) -> Event {
+        Event::NodeMove(PayloadNodeMove { id, diff, new_pos })
+    }
+
+    fn drag_start(id: usize) -> Event {
+        Event::NodeDragStart(PayloadNodeDragStart { id })
+    }
+
+    fn drag_end(id: usize) -> Event {
+        Event::NodeDragEnd(PayloadNodeDragEnd { id })
+    }
+
+    #[test]
+    fn test_undo_redo_move() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+
+        let mut history = History::default();
+        // a drag of the node in two frames
+        for event in [
+            drag_start(a.index()),
+            node_move(a.index(), [5., 0.], [5., 0.]),
+            node_move(a.index(), [5., 5.], [10., 5.]),
+            drag_end(a.index()),
+        ] {
+            history.record(&event);
+        }
+        g.node_mut(a).unwrap().set_location(Pos2::new(10., 5.));
+
+        let undone = history.undo(&mut g).unwrap();
+        assert_eq!(undone, [node_move(a.index(), [-10., -5.], [0., 0.])]);
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(0., 0.));
+        assert!(!history.can_undo());
+
+        history.redo(&mut g).unwrap();
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(10., 5.));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_separate_drags_are_separate_steps() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+
+        let mut history = History::default();
+        for pos in [[5., 0.], [10., 0.]] {
+            history.record(&drag_start(a.index()));
+            history.record(&node_move(a.index(), [5., 0.], pos));
+            history.record(&drag_end(a.index()));
+        }
+        g.node_mut(a).unwrap().set_location(Pos2::new(10., 0.));
+
+        history.undo(&mut g);
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(5., 0.));
+        history.undo(&mut g);
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(0., 0.));
+    }
+
+    #[test]
+    fn test_undo_group_drag() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+        let b = g.add_node_with_location((), Pos2::new(10., 0.));
+
+        let mut history = History::default();
+        // the dragged node moves the other selected node along, frame by frame
+        history.record(&drag_start(a.index()));
+        for i in 1..=3 {
+            let x = i as f32 * 5.;
+            history.record(&node_move(a.index(), [5., 0.], [x, 0.]));
+            history.record(&node_move(b.index(), [5., 0.], [10. + x, 0.]));
+        }
+        history.record(&drag_end(a.index()));
+        g.node_mut(a).unwrap().set_location(Pos2::new(15., 0.));
+        g.node_mut(b).unwrap().set_location(Pos2::new(25., 0.));
+
+        let undone = history.undo(&mut g).unwrap();
+        assert_eq!(undone.len(), 2);
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(0., 0.));
+        assert_eq!(g.node(b).unwrap().location(), Pos2::new(10., 0.));
+        assert!(!history.can_undo());
+
+        let redone = history.redo(&mut g).unwrap();
+        assert_eq!(
+            redone,
+            [
+                node_move(a.index(), [15., 0.], [15., 0.]),
+                node_move(b.index(), [15., 0.], [25., 0.]),
+            ]
+        );
+        assert_eq!(g.node(b).unwrap().location(), Pos2::new(25., 0.));
+    }
+
+    #[test]
+    fn test_undo_selection() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node(());
+        g.node_mut(a).unwrap().set_selected(true);
+
+        let mut history = History::default();
+        history.record(&Event::NodeSelect(PayloadNodeSelect { id: a.index() }));
+        history.undo(&mut g);
+        assert!(!g.node(a).unwrap().selected());
+
+        // recording a new step drops the undone ones
+        history.record(&Event::NodeSelect(PayloadNodeSelect { id: a.index() }));
+        assert!(!history.can_redo());
+    }
+}
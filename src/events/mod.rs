@@ -1,7 +1,11 @@
 mod event;
+mod history;
 
 pub use event::{
-    Event, PayloadEdgeClick, PayloadEdgeDeselect, PayloadEdgeSelect, PayloadNodeClick,
-    PayloadNodeDeselect, PayloadNodeDoubleClick, PayloadNodeDragEnd, PayloadNodeDragStart,
-    PayloadNodeMove, PayloadNodeSelect, PayloadPan, PayloadZoom,
+    Event, PayloadEdgeClick, PayloadEdgeCreate, PayloadEdgeDelete, PayloadEdgeDeselect,
+    PayloadEdgeHoverEnd, PayloadEdgeHoverStart, PayloadEdgeSelect, PayloadFitToScreen,
+    PayloadNodeClick, PayloadNodeDelete, PayloadNodeDeselect, PayloadNodeDoubleClick,
+    PayloadNodeDragEnd, PayloadNodeDragStart, PayloadNodeHoverEnd, PayloadNodeHoverStart,
+    PayloadNodeMove, PayloadNodeSecondaryClick, PayloadNodeSelect, PayloadPan, PayloadZoom,
 };
+pub use history::{inverse, History};
@@ -0,0 +1,363 @@
+use std::{collections::HashMap, fmt::Write};
+
+use egui::{
+    epaint::{ColorMode, PathStroke},
+    Color32, FontFamily, Pos2, Rect, Shape,
+};
+use petgraph::{stable_graph::IndexType, EdgeType};
+
+use crate::{settings::DrawOrder, DisplayEdge, DisplayNode, DrawContext, Graph};
+
+/// Exports the graph as two CSV tables, see [`crate::GraphView::to_csv`].
+pub(crate) fn to_csv<N, E, Ty, Ix, Dn, De>(g: &Graph<N, E, Ty, Ix, Dn, De>) -> (String, String)
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let mut nodes = String::from("index,label,x,y\n");
+    for (idx, n) in g.nodes_iter() {
+        let loc = n.location();
+        let _ = writeln!(
+            nodes,
+            "{},{},{},{}",
+            idx.index(),
+            escape(&n.label()),
+            loc.x,
+            loc.y
+        );
+    }
+
+    let mut edges = String::from("source,target,label\n");
+    for (idx, e) in g.edges_iter() {
+        let Some((start, end)) = g.edge_endpoints(idx) else {
+            continue;
+        };
+        let _ = writeln!(
+            edges,
+            "{},{},{}",
+            start.index(),
+            end.index(),
+            escape(&e.label())
+        );
+    }
+
+    (nodes, edges)
+}
+
+/// Quotes the CSV field if it contains separators, quotes or line breaks.
+fn escape(field: &str) -> String {
+    if !field.contains([',', '"', '\n', '\r']) {
+        return field.to_string();
+    }
+
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+/// Creates the shapes of nodes and edges in the order they are drawn by the widget.
+///
+/// Displays are updated on their copies, so the graph is left untouched.
+pub(crate) fn graph_shapes<N, E, Ty, Ix, Dn, De>(
+    g: &Graph<N, E, Ty, Ix, Dn, De>,
+    ctx: &DrawContext,
+) -> Vec<Shape>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    // edges are placed at the boundaries of node displays, which need to be up to date too
+    let mut nodes = g
+        .nodes_iter()
+        .map(|(idx, n)| {
+            let mut n = n.clone();
+            let props = n.props().clone();
+            n.display_mut().update(&props);
+            (idx, n)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut shapes = vec![];
+    let mut postponed = vec![];
+
+    for (idx, e) in g.edges_iter() {
+        let Some((start, end)) = g.edge_endpoints(idx) else {
+            continue;
+        };
+        let (Some(start), Some(end)) = (nodes.get(&start), nodes.get(&end)) else {
+            continue;
+        };
+
+        let mut display = e.display().clone();
+        display.update(e.props());
+        let edge_shapes = display.shapes(start, end, ctx);
+        if e.selected() {
+            postponed.extend(edge_shapes);
+        } else {
+            shapes.extend(edge_shapes);
+        }
+    }
+
+    let order = ctx.style.draw_order;
+    for idx in g.nodes_draw_order(order) {
+        let Some(n) = nodes.get_mut(&idx) else {
+            continue;
+        };
+
+        let interacted = n.selected() || n.dragged();
+        let node_shapes = n.display_mut().shapes(ctx);
+        if order == DrawOrder::SelectedLast && interacted {
+            postponed.extend(node_shapes);
+        } else {
+            shapes.extend(node_shapes);
+        }
+    }
+
+    shapes.extend(postponed);
+    shapes
+}
+
+/// Exports the shapes as an SVG document showing the `rect` of the screen,
+/// see [`crate::GraphView::to_svg`].
+pub(crate) fn to_svg(shapes: &[Shape], rect: Rect) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\">\n",
+        rect.min.x,
+        rect.min.y,
+        rect.width(),
+        rect.height(),
+        rect.width(),
+        rect.height()
+    );
+    for s in shapes {
+        push_svg_shape(&mut svg, s);
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// Appends the SVG element of the shape. Images, meshes and callbacks have no SVG counterpart
+/// and are skipped.
+fn push_svg_shape(svg: &mut String, shape: &Shape) {
+    match shape {
+        Shape::Vec(shapes) => {
+            for s in shapes {
+                push_svg_shape(svg, s);
+            }
+        }
+        Shape::Circle(c) => {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"{}{}/>",
+                c.center.x,
+                c.center.y,
+                c.radius,
+                svg_paint("fill", c.fill),
+                svg_stroke(c.stroke.width, c.stroke.color),
+            );
+        }
+        Shape::LineSegment { points, stroke } => {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{}/>",
+                points[0].x,
+                points[0].y,
+                points[1].x,
+                points[1].y,
+                svg_path_stroke(stroke),
+            );
+        }
+        Shape::Path(p) => {
+            let (element, fill) = if p.closed {
+                ("polygon", p.fill)
+            } else {
+                ("polyline", Color32::TRANSPARENT)
+            };
+            let _ = writeln!(
+                svg,
+                "<{element} points=\"{}\"{}{}/>",
+                svg_points(&p.points),
+                svg_paint("fill", fill),
+                svg_path_stroke(&p.stroke),
+            );
+        }
+        Shape::CubicBezier(c) => {
+            svg.push_str(&svg_curve('C', &c.points, c.closed, c.fill, &c.stroke));
+        }
+        Shape::QuadraticBezier(q) => {
+            svg.push_str(&svg_curve('Q', &q.points, q.closed, q.fill, &q.stroke));
+        }
+        Shape::Rect(r) => {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\"{}{}/>",
+                r.rect.min.x,
+                r.rect.min.y,
+                r.rect.width(),
+                r.rect.height(),
+                r.rounding.nw,
+                svg_paint("fill", r.fill),
+                svg_stroke(r.stroke.width, r.stroke.color),
+            );
+        }
+        Shape::Text(t) => {
+            let font = t.galley.job.sections.first().map(|s| &s.format.font_id);
+            let size = font.map_or(14., |f| f.size);
+            let family = match font.map(|f| &f.family) {
+                Some(FontFamily::Monospace) => "monospace",
+                _ => "sans-serif",
+            };
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" font-size=\"{size}\" font-family=\"{family}\" dominant-baseline=\"hanging\"{}>{}</text>",
+                t.pos.x,
+                t.pos.y,
+                svg_paint("fill", t.override_text_color.unwrap_or(t.fallback_color)),
+                escape_xml(t.galley.text()),
+            );
+        }
+        _ => {}
+    }
+}
+
+fn svg_curve(
+    command: char,
+    points: &[Pos2],
+    closed: bool,
+    fill: Color32,
+    stroke: &PathStroke,
+) -> String {
+    let fill = if closed { fill } else { Color32::TRANSPARENT };
+    let close = if closed { " Z" } else { "" };
+    format!(
+        "<path d=\"M {} {command} {}{close}\"{}{}/>\n",
+        svg_points(&points[..1]),
+        svg_points(&points[1..]),
+        svg_paint("fill", fill),
+        svg_path_stroke(stroke),
+    )
+}
+
+fn svg_points(points: &[Pos2]) -> String {
+    points
+        .iter()
+        .map(|p| format!("{},{}", p.x, p.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Paint attribute with the opacity attribute for translucent colors.
+fn svg_paint(attr: &str, color: Color32) -> String {
+    if color.a() == 0 {
+        return format!(" {attr}=\"none\"");
+    }
+
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let mut res = format!(" {attr}=\"#{r:02x}{g:02x}{b:02x}\"");
+    if a < u8::MAX {
+        let _ = write!(res, " {attr}-opacity=\"{}\"", f32::from(a) / 255.);
+    }
+
+    res
+}
+
+fn svg_stroke(width: f32, color: Color32) -> String {
+    if width <= 0. {
+        return svg_paint("stroke", Color32::TRANSPARENT);
+    }
+
+    format!("{} stroke-width=\"{width}\"", svg_paint("stroke", color))
+}
+
+/// Strokes with colors computed per point have no SVG counterpart and are left out.
+fn svg_path_stroke(stroke: &PathStroke) -> String {
+    match stroke.color {
+        ColorMode::Solid(color) => svg_stroke(stroke.width, color),
+        ColorMode::UV(_) => svg_stroke(0., Color32::TRANSPARENT),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+    use crate::{DefaultGraphView, Metadata, SettingsStyle};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_svg_paint() {
+        assert_eq!(svg_paint("fill", Color32::TRANSPARENT), " fill=\"none\"");
+        assert_eq!(
+            svg_paint("fill", Color32::from_rgb(255, 0, 16)),
+            " fill=\"#ff0010\""
+        );
+        assert_eq!(
+            svg_paint("stroke", Color32::from_rgba_unmultiplied(0, 0, 255, 51)),
+            " stroke=\"#0000ff\" stroke-opacity=\"0.2\""
+        );
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+        let b = g.add_node_with_location((), Pos2::new(50., 0.));
+        let c = g.add_node_with_location((), Pos2::new(50., 50.));
+        g.add_edge(a, b, ());
+        g.add_edge(b, c, ());
+
+        let count = |svg: &str, element: &str| svg.matches(&format!("<{element} ")).count();
+        let meta = Metadata::default();
+
+        let svg = DefaultGraphView::new(&mut g).to_svg(&meta);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(count(&svg, "circle"), 3);
+        assert_eq!(count(&svg, "line"), 2);
+        // arrow tips
+        assert_eq!(count(&svg, "polygon"), 2);
+        assert_eq!(count(&svg, "text"), 0);
+
+        let style = SettingsStyle::new().with_labels_always(true);
+        let svg = DefaultGraphView::new(&mut g)
+            .with_styles(&style)
+            .to_svg(&meta);
+        assert_eq!(count(&svg, "circle"), 3);
+        // labels of the nodes and of the edges
+        assert_eq!(count(&svg, "text"), 5);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_label_and_location((), "a, first".to_string(), Pos2::new(1., 2.));
+        let b = g.add_node_with_label_and_location((), "b".to_string(), Pos2::new(-3.5, 0.));
+        g.add_edge_with_label(a, b, (), "a->b".to_string());
+
+        let (nodes, edges) = to_csv(&g);
+        assert_eq!(nodes, "index,label,x,y\n0,\"a, first\",1,2\n1,b,-3.5,0\n");
+        assert_eq!(edges, "source,target,label\n0,1,a->b\n");
+    }
+}
@@ -1,4 +1,6 @@
-use egui::Pos2;
+use std::{collections::HashSet, f32::consts::TAU};
+
+use egui::{Pos2, Rect, Vec2};
 use petgraph::stable_graph::DefaultIx;
 use petgraph::Directed;
 
@@ -11,14 +13,27 @@ use petgraph::{
 use serde::{Deserialize, Serialize};
 
 use crate::draw::{DisplayEdge, DisplayNode};
-use crate::{metadata::Metadata, Edge, Node};
+use crate::spatial_index::SpatialIndex;
+use crate::{metadata::Metadata, settings::DrawOrder, Edge, Node};
 use crate::{to_graph, DefaultEdgeShape, DefaultNodeShape};
 
+/// Number of directions in which node outlines are sampled to bound nodes.
+const NODES_INDEX_DIRECTIONS: usize = 16;
+
+/// Relative padding of sampled node bounds.
+const NODES_INDEX_MARGIN: f32 = 0.1;
+
 type StableGraphType<N, E, Ty, Ix, Dn, De> =
     StableGraph<Node<N, E, Ty, Ix, Dn>, Edge<N, E, Ty, Ix, Dn, De>, Ty, Ix>;
 
 /// Wrapper around [`petgraph::stable_graph::StableGraph`] compatible with [`super::GraphView`].
 /// It is used to store graph data and provide access to it.
+///
+/// The widget keeps node and edge indices across frames, e.g. for the selection, the dragged
+/// and hovered node and the interaction log, and looks elements up by index with
+/// `node_weight_mut` and `edge_weight_mut`. Removing an element must not invalidate the
+/// indices of the others, which is why a `StableGraph` is used. A [`petgraph::Graph`] can be
+/// converted with [`From`], keeping its indices.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Graph<
     N = (),
@@ -39,6 +54,14 @@ pub struct Graph<
     selected_nodes: Vec<NodeIndex<Ix>>,
     selected_edges: Vec<EdgeIndex<Ix>>,
     dragged_node: Option<NodeIndex<Ix>>,
+    hovered_node: Option<NodeIndex<Ix>>,
+    #[serde(default)]
+    hovered_edge: Option<EdgeIndex<Ix>>,
+    secondary_clicked_node: Option<NodeIndex<Ix>>,
+    #[serde(skip)]
+    nodes_index: Option<SpatialIndex<Ix>>,
+    #[serde(skip)]
+    nodes_order: Option<(DrawOrder, Vec<NodeIndex<Ix>>)>,
 }
 
 impl<N, E, Ty, Ix, Dn, De> From<&StableGraph<N, E, Ty, Ix>> for Graph<N, E, Ty, Ix, Dn, De>
@@ -55,6 +78,37 @@ where
     }
 }
 
+/// Converts a [`petgraph::Graph`] with the default transforms like [`to_graph`].
+///
+/// Nodes and edges keep their indices, so indices of the source graph can be used with the
+/// resulting graph until elements are removed from either.
+///
+/// ```
+/// use egui_graphs::Graph;
+///
+/// let mut g = petgraph::Graph::<&str, &str>::new();
+/// let a = g.add_node("A");
+/// let b = g.add_node("B");
+/// g.add_edge(a, b, "edge1");
+///
+/// let result: Graph<_, _> = Graph::from(&g);
+/// assert_eq!(*result.node(b).unwrap().payload(), "B");
+/// assert_eq!(result.edge_count(), 1);
+/// ```
+impl<N, E, Ty, Ix, Dn, De> From<&petgraph::Graph<N, E, Ty, Ix>> for Graph<N, E, Ty, Ix, Dn, De>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    fn from(g: &petgraph::Graph<N, E, Ty, Ix>) -> Self {
+        to_graph(&StableGraph::from(g.clone()))
+    }
+}
+
 impl<N, E, Ty, Ix, Dn, De> Graph<N, E, Ty, Ix, Dn, De>
 where
     N: Clone,
@@ -70,19 +124,103 @@ where
             selected_nodes: Vec::default(),
             selected_edges: Vec::default(),
             dragged_node: Option::default(),
+            hovered_node: Option::default(),
+            hovered_edge: Option::default(),
+            secondary_clicked_node: Option::default(),
+            nodes_index: Option::default(),
+            nodes_order: Option::default(),
         }
     }
 
-    /// Finds node by position. Can be optimized by using a spatial index like quad-tree if needed.
+    /// Finds the topmost node by position assuming the default [`DrawOrder`].
     pub fn node_by_screen_pos(&self, meta: &Metadata, screen_pos: Pos2) -> Option<NodeIndex<Ix>> {
+        self.node_by_screen_pos_ordered(meta, screen_pos, DrawOrder::default())
+    }
+
+    /// Finds the topmost node by position for nodes drawn in the given `order`,
+    /// so that the found node is the one visible under the cursor when nodes overlap.
+    pub fn node_by_screen_pos_ordered(
+        &self,
+        meta: &Metadata,
+        screen_pos: Pos2,
+        order: DrawOrder,
+    ) -> Option<NodeIndex<Ix>> {
         let pos_in_graph = meta.screen_to_canvas_pos(screen_pos);
-        for (idx, node) in self.nodes_iter() {
-            let display = node.display();
-            if display.is_inside(pos_in_graph) {
-                return Some(idx);
-            }
+        let Some(index) = &self.nodes_index else {
+            let is_hit = |idx: &NodeIndex<Ix>| self.g[*idx].display().is_inside(pos_in_graph);
+            return match &self.nodes_order {
+                Some((drawn, idxs)) if *drawn == order => idxs.iter().rev().copied().find(is_hit),
+                _ => self.nodes_draw_order(order).into_iter().rev().find(is_hit),
+            };
+        };
+
+        // candidates are ranked the same way as in `nodes_draw_order`
+        index
+            .candidates(pos_in_graph)
+            .filter_map(|idx| self.g.node_weight(idx).map(|n| (idx, n)))
+            .filter(|(_, n)| n.display().is_inside(pos_in_graph))
+            .max_by_key(|(idx, n)| (Self::draw_rank(n, order), idx.index()))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Rebuilds the spatial index used to find nodes by position from the current displays.
+    ///
+    /// Displays are updated when nodes are drawn, so the index is rebuilt after that. Adding or
+    /// removing nodes drops the index, and nodes are searched one by one until the next rebuild.
+    pub(crate) fn update_nodes_index(&mut self) {
+        let rects = self
+            .g
+            .node_indices()
+            .map(|idx| (idx, Self::display_bounds(self.g[idx].display())))
+            .collect::<Vec<_>>();
+
+        self.nodes_index = Some(SpatialIndex::new(&rects));
+    }
+
+    /// Sorts the nodes in the given draw order and keeps the order for hit-testing, so that
+    /// nodes are sorted once per frame. Like the spatial index, the order is dropped when
+    /// nodes are added or removed.
+    pub(crate) fn update_nodes_order(&mut self, order: DrawOrder) -> &[NodeIndex<Ix>] {
+        let idxs = self.nodes_draw_order(order);
+        &self.nodes_order.insert((order, idxs)).1
+    }
+
+    /// Drops the spatial index and the draw order, which do not know about added or removed nodes.
+    fn invalidate_nodes_lookup(&mut self) {
+        self.nodes_index = None;
+        self.nodes_order = None;
+    }
+
+    /// Bounding rect of the node display in canvas coordinates, found by sampling its outline.
+    pub(crate) fn display_bounds(display: &Dn) -> Rect {
+        let points = (0..NODES_INDEX_DIRECTIONS)
+            .map(|i| {
+                let angle = i as f32 * TAU / NODES_INDEX_DIRECTIONS as f32;
+                display.closest_boundary_point(Vec2::angled(angle))
+            })
+            .collect::<Vec<_>>();
+
+        // sampled outlines may miss corners lying between the directions
+        let rect = Rect::from_points(&points);
+        rect.expand2(rect.size() * NODES_INDEX_MARGIN)
+    }
+
+    /// Returns indices of all nodes in the order they are drawn with the given [`DrawOrder`].
+    pub fn nodes_draw_order(&self, order: DrawOrder) -> Vec<NodeIndex<Ix>> {
+        let mut idxs = self.g.node_indices().collect::<Vec<_>>();
+        // sort is stable so the insertion order is kept among nodes of the same rank
+        idxs.sort_by_key(|idx| Self::draw_rank(&self.g[*idx], order));
+
+        idxs
+    }
+
+    /// Nodes are drawn in the ascending order of their ranks.
+    fn draw_rank(n: &Node<N, E, Ty, Ix, Dn>, order: DrawOrder) -> (bool, i32) {
+        match order {
+            DrawOrder::Insertion => (false, 0),
+            DrawOrder::ByZ => (false, n.z()),
+            DrawOrder::SelectedLast => (n.selected() || n.dragged(), n.z()),
         }
-        None
     }
 
     /// Finds edge by position.
@@ -103,6 +241,36 @@ where
         None
     }
 
+    /// Finds edge by position, accepting positions up to `tolerance` screen points away from
+    /// the edge, see [`DisplayEdge::is_inside_with_tolerance`].
+    ///
+    /// The tolerance is converted to canvas units with the zoom, so it stays the same on the
+    /// screen at any zoom level.
+    #[allow(clippy::missing_panics_doc)] // TODO: add panics doc
+    pub fn edge_by_screen_pos_with_tolerance(
+        &self,
+        meta: &Metadata,
+        screen_pos: Pos2,
+        tolerance: f32,
+    ) -> Option<EdgeIndex<Ix>> {
+        let pos_in_graph = meta.screen_to_canvas_pos(screen_pos);
+        let tolerance = tolerance / meta.zoom;
+        for (idx, e) in self.edges_iter() {
+            let Some((idx_start, idx_end)) = self.g.edge_endpoints(e.id()) else {
+                continue;
+            };
+            let start = self.g.node_weight(idx_start).unwrap();
+            let end = self.g.node_weight(idx_end).unwrap();
+            if e.display()
+                .is_inside_with_tolerance(start, end, pos_in_graph, tolerance)
+            {
+                return Some(idx);
+            }
+        }
+
+        None
+    }
+
     pub fn g(&mut self) -> &mut StableGraphType<N, E, Ty, Ix, Dn, De> {
         &mut self.g
     }
@@ -113,6 +281,7 @@ where
         let node = Node::new(payload);
 
         let idx = self.g.add_node(node);
+        self.invalidate_nodes_lookup();
         let graph_node = self.g.node_weight_mut(idx).unwrap();
 
         graph_node.set_id(idx);
@@ -128,6 +297,7 @@ where
         let node = Node::new(payload);
 
         let idx = self.g.add_node(node);
+        self.invalidate_nodes_lookup();
         let graph_node = self.g.node_weight_mut(idx).unwrap();
 
         graph_node.set_id(idx);
@@ -153,6 +323,7 @@ where
         let node = Node::new(payload);
 
         let idx = self.g.add_node(node);
+        self.invalidate_nodes_lookup();
         let graph_node = self.g.node_weight_mut(idx).unwrap();
 
         graph_node.set_id(idx);
@@ -171,6 +342,7 @@ where
             self.remove_edges_between(*n, idx);
         }
 
+        self.invalidate_nodes_lookup();
         self.g.remove_node(idx)
     }
 
@@ -319,6 +491,23 @@ where
         self.g.edges_directed(idx, dir)
     }
 
+    /// Provides iterator over indices of all edges incoming to or outgoing from the node.
+    ///
+    /// Self-loops are returned twice.
+    pub fn incident_edges(&self, idx: NodeIndex<Ix>) -> impl Iterator<Item = EdgeIndex<Ix>> + '_ {
+        self.g
+            .edges_directed(idx, Direction::Outgoing)
+            .chain(self.g.edges_directed(idx, Direction::Incoming))
+            .map(|e| e.id())
+    }
+
+    /// Returns the node together with its neighbors connected by edges in either direction.
+    pub fn neighborhood(&self, idx: NodeIndex<Ix>) -> HashSet<NodeIndex<Ix>> {
+        let mut res = self.g.neighbors_undirected(idx).collect::<HashSet<_>>();
+        res.insert(idx);
+        res
+    }
+
     pub fn selected_nodes(&self) -> &[NodeIndex<Ix>] {
         &self.selected_nodes
     }
@@ -343,6 +532,34 @@ where
         self.dragged_node = node;
     }
 
+    pub fn hovered_node(&self) -> Option<NodeIndex<Ix>> {
+        self.hovered_node
+    }
+
+    pub fn set_hovered_node(&mut self, node: Option<NodeIndex<Ix>>) {
+        self.hovered_node = node;
+    }
+
+    pub fn hovered_edge(&self) -> Option<EdgeIndex<Ix>> {
+        self.hovered_edge
+    }
+
+    pub fn set_hovered_edge(&mut self, edge: Option<EdgeIndex<Ix>>) {
+        self.hovered_edge = edge;
+    }
+
+    /// Node hit by the last secondary click on a node.
+    ///
+    /// Kept until the next secondary click on a node so it can be used in
+    /// [`egui::Response::context_menu`], which stays open over several frames.
+    pub fn secondary_clicked_node(&self) -> Option<NodeIndex<Ix>> {
+        self.secondary_clicked_node
+    }
+
+    pub fn set_secondary_clicked_node(&mut self, node: Option<NodeIndex<Ix>>) {
+        self.secondary_clicked_node = node;
+    }
+
     pub fn edge_count(&self) -> usize {
         self.g.edge_count()
     }
@@ -1,14 +1,19 @@
-use std::marker::PhantomData;
+use std::{collections::HashSet, marker::PhantomData};
 
 use crate::{
-    draw::{DefaultEdgeShape, DefaultNodeShape, DrawContext, Drawer},
+    draw::{Annotation, DefaultEdgeShape, DefaultNodeShape, DrawContext, Drawer, Minimap},
+    export,
+    interaction::Interaction,
     layouts::{self, Layout, LayoutState},
-    metadata::Metadata,
+    metadata::{labels_bounds, nodes_bounds, Metadata},
     settings::{SettingsInteraction, SettingsNavigation, SettingsStyle},
-    DisplayEdge, DisplayNode, Graph,
+    DisplayEdge, DisplayNode, Edge, Graph, Node,
 };
 
-use egui::{Id, PointerButton, Pos2, Rect, Response, Sense, Ui, Vec2, Widget};
+use egui::{
+    emath::Rot2, show_tooltip_at_pointer, Context, EventFilter, Id, InputState, Key, LayerId,
+    Painter, PointerButton, Pos2, Rangef, Rect, Response, Sense, Ui, Vec2, Widget, WidgetText,
+};
 
 use petgraph::{graph::EdgeIndex, stable_graph::DefaultIx};
 use petgraph::{graph::IndexType, Directed};
@@ -16,6 +21,18 @@ use petgraph::{stable_graph::NodeIndex, EdgeType};
 
 const KEY_LAYOUT: &str = "egui_grpahs_layout";
 
+/// Distance in screen points within which a recorded drag frame continues the previous one.
+const DRAG_CONTINUITY: f32 = 0.5;
+
+/// Size in screen points below which a dragged zoom box is ignored.
+const MIN_ZOOM_BOX_SIZE: f32 = 4.;
+
+/// Padding in screen points around the graph exported before the widget was drawn.
+const SVG_MARGIN: f32 = 20.;
+
+/// Returns the tooltip text of a hovered node, see [`GraphView::with_node_tooltip`].
+type NodeTooltip<'a, N, Ix> = &'a dyn Fn(NodeIndex<Ix>, &N) -> Option<WidgetText>;
+
 pub type DefaultGraphView<'a> = GraphView<
     'a,
     (),
@@ -30,12 +47,16 @@ pub type DefaultGraphView<'a> = GraphView<
 
 #[cfg(feature = "events")]
 use crate::events::{
-    Event, PayloadEdgeClick, PayloadEdgeDeselect, PayloadEdgeSelect, PayloadNodeClick,
-    PayloadNodeDeselect, PayloadNodeDoubleClick, PayloadNodeDragEnd, PayloadNodeDragStart,
-    PayloadNodeMove, PayloadNodeSelect, PayloadPan, PayloadZoom,
+    Event, PayloadEdgeClick, PayloadEdgeCreate, PayloadEdgeDelete, PayloadEdgeDeselect,
+    PayloadEdgeHoverEnd, PayloadEdgeHoverStart, PayloadEdgeSelect, PayloadFitToScreen,
+    PayloadNodeClick, PayloadNodeDelete, PayloadNodeDeselect, PayloadNodeDoubleClick,
+    PayloadNodeDragEnd, PayloadNodeDragStart, PayloadNodeHoverEnd, PayloadNodeHoverStart,
+    PayloadNodeMove, PayloadNodeSecondaryClick, PayloadNodeSelect, PayloadPan, PayloadZoom,
 };
 #[cfg(feature = "events")]
 use crossbeam::channel::Sender;
+#[cfg(feature = "events")]
+use std::cell::RefCell;
 
 /// Widget for visualizing and interacting with graphs.
 ///
@@ -77,9 +98,14 @@ pub struct GraphView<
     settings_interaction: SettingsInteraction,
     settings_navigation: SettingsNavigation,
     settings_style: SettingsStyle,
+    annotations: &'a [Annotation],
+    interaction_log: Option<&'a mut Vec<Interaction>>,
+    node_tooltip: Option<NodeTooltip<'a, N, Ix>>,
 
     #[cfg(feature = "events")]
     events_publisher: Option<&'a Sender<Event>>,
+    #[cfg(feature = "events")]
+    last_events: RefCell<Vec<Event>>,
 
     _marker: PhantomData<(Nd, Ed, L, S)>,
 }
@@ -96,16 +122,40 @@ where
     L: Layout<S>,
 {
     fn ui(self, ui: &mut Ui) -> Response {
-        self.sync_layout(ui);
+        #[cfg(feature = "events")]
+        self.last_events.get_mut().clear();
+
+        let layout_moved = self.sync_layout(ui);
 
         let mut meta = Metadata::load(ui);
         self.sync_state(&mut meta);
 
         let (resp, p) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
         self.handle_fit_to_screen(&resp, &mut meta);
+        self.handle_minimap(ui, &resp, &mut meta);
+        // the pointer pressed in the minimap does not reach the graph below it
+        let on_minimap = meta.minimap_drag();
+        if !on_minimap {
+            self.handle_box_selection(ui, &resp, &mut meta);
+            self.handle_marquee_zoom(ui, &resp, &mut meta);
+        }
         self.handle_navigation(ui, &resp, &mut meta);
-        self.handle_node_drag(&resp, &mut meta);
-        self.handle_click(&resp, &mut meta);
+        self.animate_camera(ui, &mut meta);
+        if !on_minimap {
+            self.handle_edge_creation(ui, &resp, &mut meta);
+            self.handle_node_drag(&resp, &mut meta);
+            self.handle_click(&resp, &mut meta);
+            self.handle_secondary_click(&resp, &meta);
+        }
+        self.handle_hover(&resp, &meta);
+        self.show_node_tooltip(&resp);
+        self.handle_escape_key(ui, &resp);
+        self.handle_select_all_key(ui, &resp);
+        #[cfg(feature = "events")]
+        self.handle_delete_key(ui, &resp);
+        let settling = self.sync_rendered_locations(ui, &mut meta);
+        // computed ahead as the drawer borrows the graph mutably
+        let minimap = self.minimap(resp.rect, &meta);
 
         Drawer::<N, E, Ty, Ix, Nd, Ed, S, L>::new(
             self.g,
@@ -117,12 +167,23 @@ where
                 style: &self.settings_style,
             },
         )
+        .with_annotations(self.annotations)
+        .with_grid(self.settings_interaction.snap_to_grid)
+        .with_minimap(minimap)
         .draw();
 
+        let repaint = self.settings_navigation.continuous_repaint
+            || meta.first_frame
+            || layout_moved
+            || settling
+            || self.is_moving(ui, &resp, &meta);
+
         meta.first_frame = false;
         meta.save(ui);
 
-        ui.ctx().request_repaint();
+        if repaint {
+            ui.ctx().request_repaint();
+        }
 
         resp
     }
@@ -148,9 +209,14 @@ where
             settings_style: SettingsStyle::default(),
             settings_interaction: SettingsInteraction::default(),
             settings_navigation: SettingsNavigation::default(),
+            annotations: &[],
+            interaction_log: Option::default(),
+            node_tooltip: Option::default(),
 
             #[cfg(feature = "events")]
             events_publisher: Option::default(),
+            #[cfg(feature = "events")]
+            last_events: RefCell::default(),
 
             _marker: PhantomData,
         }
@@ -174,6 +240,215 @@ where
         self
     }
 
+    /// Draws the annotations on top of the graph. Annotations are positioned in graph
+    /// coordinates, so they follow zoom and pan, and do not react to interactions.
+    pub fn with_annotations(mut self, annotations: &'a [Annotation]) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Shows a tooltip at the pointer while a node is hovered. The callback receives the
+    /// hovered node and its payload and returns the tooltip text, or `None` for no tooltip.
+    ///
+    /// Tooltips are shown only with [`SettingsInteraction::with_node_hover_enabled`].
+    pub fn with_node_tooltip(mut self, tooltip: NodeTooltip<'a, N, Ix>) -> Self {
+        self.node_tooltip = Some(tooltip);
+        self
+    }
+
+    /// Toggles selection of the node the same way as clicking it does.
+    ///
+    /// A selected node is deselected. Otherwise the node is selected and, unless multiselection
+    /// is enabled, everything else is deselected. Does nothing if node selection is disabled
+    /// in [`SettingsInteraction`] or the node does not exist. Selection events are published
+    /// as for a click, but no click event is.
+    ///
+    /// Allows driving selection from the keyboard or from an external list of nodes.
+    pub fn toggle_node_selection(&mut self, idx: NodeIndex<Ix>) {
+        if !self.settings_interaction.node_selection_enabled {
+            return;
+        }
+
+        let Some(n) = self.g.node(idx) else {
+            return;
+        };
+        if n.selected() {
+            self.deselect_node(idx);
+        } else {
+            if !self.settings_interaction.node_selection_multi_enabled {
+                self.deselect_all();
+            }
+            self.select_node(idx);
+        }
+
+        self.refresh_selection();
+    }
+
+    /// Records interactions of the user with the widget to the `log`.
+    ///
+    /// The log is serializable and can be replayed with [`Self::replay`] to reproduce the state
+    /// of the graph, e.g. in tests or recorded demos.
+    pub fn with_interaction_log(mut self, log: &'a mut Vec<Interaction>) -> Self {
+        self.interaction_log = Some(log);
+        self
+    }
+
+    /// Applies the recorded `log` of interactions to the graph as if the user performed them
+    /// in a widget occupying the `rect` with the navigation state `meta`.
+    ///
+    /// Interactions are handled according to the current settings and publish the same events
+    /// as live interactions. Consecutive node drags continuing where the previous one ended are
+    /// replayed as a single drag, recorded by the live widget frame by frame. Node drags are
+    /// replayed without the compensation of node movement made by the live widget, so replaying
+    /// drags is exact only for nodes which are not moved by a layout at the same time.
+    pub fn replay(&mut self, rect: Rect, meta: &mut Metadata, log: &[Interaction]) {
+        // dragged node with the screen position where the last drag frame ended
+        let mut drag: Option<(NodeIndex<Ix>, Pos2)> = None;
+        for interaction in log {
+            // hit-testing relies on the displays being in sync with the elements
+            self.sync_displays();
+            self.g.update_nodes_index();
+            self.sync_state(meta);
+
+            if !matches!(interaction, Interaction::NodeDrag { .. }) {
+                self.end_replayed_drag(drag.take());
+            }
+            match interaction {
+                Interaction::Click { pos } => self.click_at((*pos).into(), false, meta),
+                Interaction::DoubleClick { pos } => self.click_at((*pos).into(), true, meta),
+                Interaction::NodeDrag { from, delta } => {
+                    drag = self.replay_drag(drag, (*from).into(), (*delta).into(), meta);
+                }
+                Interaction::Zoom { delta, center } => {
+                    self.zoom_by(&rect, *delta, center.map(Pos2::from), meta);
+                }
+                Interaction::Pan { delta } => self.apply_pan(meta.pan + Vec2::from(*delta), meta),
+            }
+        }
+        self.end_replayed_drag(drag);
+
+        self.sync_state(meta);
+    }
+
+    /// Rotates locations of all nodes by `radians` around the `center` given in graph
+    /// coordinates, or around the centroid of the nodes if `center` is `None`.
+    ///
+    /// Positive angles rotate clockwise on the screen as the y axis points down. Every moved
+    /// node publishes a node move event. Useful to align a layout to a preferred axis
+    /// without recomputing it.
+    pub fn rotate_layout(&mut self, radians: f32, center: Option<Vec2>) {
+        let locations = self
+            .g
+            .nodes_iter()
+            .map(|(idx, n)| (idx, n.location().to_vec2()))
+            .collect::<Vec<_>>();
+        if locations.is_empty() {
+            return;
+        }
+
+        let center = center.unwrap_or_else(|| {
+            locations
+                .iter()
+                .fold(Vec2::ZERO, |acc, (_, loc)| acc + *loc)
+                / locations.len() as f32
+        });
+        let rot = Rot2::from_angle(radians);
+        for (idx, loc) in locations {
+            let rotated = center + rot * (loc - center);
+            self.move_node(idx, rotated - loc);
+        }
+    }
+
+    /// Exports the graph as CSV tables of nodes and edges.
+    ///
+    /// Nodes table has `index,label,x,y` columns with current node locations, edges table has
+    /// `source,target,label` columns referencing node indices. Both tables start with a header
+    /// row. Labels containing commas, quotes or line breaks are quoted.
+    pub fn to_csv(&self) -> (String, String) {
+        export::to_csv(self.g)
+    }
+
+    /// Exports the view as an SVG document.
+    ///
+    /// Nodes and edges are drawn by their displays with the current style settings and the pan
+    /// and zoom of the `meta`, so the document matches the widget as of the frame the metadata
+    /// was loaded in. The document covers the widget rect, or the whole graph if the widget was
+    /// not drawn yet. Colors come from the default `egui` style. Node images are left out.
+    pub fn to_svg(&self, meta: &Metadata) -> String {
+        let rect = if meta.rect.is_positive() {
+            meta.rect
+        } else {
+            self.graph_bounds().map_or(Rect::ZERO, |b| {
+                Rect::from_min_max(
+                    meta.canvas_to_screen_pos(b.min),
+                    meta.canvas_to_screen_pos(b.max),
+                )
+                .expand(SVG_MARGIN)
+            })
+        };
+
+        // fonts for labels are available only within a frame
+        let ctx = egui::Context::default();
+        let mut shapes = vec![];
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            let painter = Painter::new(ctx.clone(), LayerId::background(), rect);
+            shapes = export::graph_shapes(
+                self.g,
+                &DrawContext {
+                    ctx,
+                    painter: &painter,
+                    meta,
+                    is_directed: self.g.is_directed(),
+                    style: &self.settings_style,
+                },
+            );
+        });
+
+        export::to_svg(&shapes, rect)
+    }
+
+    /// Returns the graph displayed by the widget.
+    ///
+    /// The returned reference borrows the view immutably, so it has to be dropped before the view
+    /// is used mutably again, e.g. added to the [`Ui`]. Call it after `ui.add(&mut view)` to read the
+    /// values computed by the widget during the frame, like node locations or display properties.
+    pub fn graph(&self) -> &Graph<N, E, Ty, Ix, Dn, De> {
+        self.g
+    }
+
+    /// Returns indices of the selected nodes in the order of their indices.
+    ///
+    /// Nodes are read from their selection flags, so the result is up to date even with
+    /// selections changed outside of the widget since the last frame, and it does not depend
+    /// on events being published.
+    pub fn selected_nodes(&self) -> Vec<NodeIndex<Ix>> {
+        self.g
+            .nodes_iter()
+            .filter(|(_, n)| n.selected())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Returns indices of the selected edges in the order of their indices,
+    /// see [`GraphView::selected_nodes`].
+    pub fn selected_edges(&self) -> Vec<EdgeIndex<Ix>> {
+        self.g
+            .edges_iter()
+            .filter(|(_, e)| e.selected())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Returns `false` for nodes not in the graph.
+    pub fn is_node_selected(&self, idx: NodeIndex<Ix>) -> bool {
+        self.g.node(idx).is_some_and(Node::selected)
+    }
+
+    /// Returns `false` for edges not in the graph.
+    pub fn is_edge_selected(&self, idx: EdgeIndex<Ix>) -> bool {
+        self.g.edge(idx).is_some_and(Edge::selected)
+    }
+
     /// Clears cached values of layout and metadata.
     pub fn clear_cache(ui: &mut Ui) {
         GraphView::<N, E, Ty, Ix, Dn, De, S, L>::reset_metadata(ui);
@@ -185,6 +460,150 @@ where
         Metadata::default().save(ui);
     }
 
+    /// Returns the zoom of the view stored in egui memory.
+    pub fn zoom(ui: &Ui) -> f32 {
+        Metadata::load(ui).zoom
+    }
+
+    /// Returns the pan of the view stored in egui memory.
+    pub fn pan(ui: &Ui) -> Vec2 {
+        Metadata::load(ui).pan
+    }
+
+    /// Sets the zoom of the view, clamped to the zoom limits. The pan is left unchanged.
+    ///
+    /// Takes effect on the next frame. Combine with [`GraphView::keep_view`] to restore a view
+    /// saved in a previous session, otherwise the first frame fits the graph to the screen.
+    pub fn set_zoom(&self, ui: &mut Ui, zoom: f32) {
+        let mut meta = Metadata::load(ui);
+        meta.clear_camera_target();
+        let zoom = zoom.clamp(
+            self.settings_navigation.zoom_min,
+            self.settings_navigation.zoom_max,
+        );
+        self.apply_zoom(zoom, &mut meta);
+        meta.save(ui);
+    }
+
+    /// Sets the pan of the view. The zoom is left unchanged.
+    ///
+    /// Takes effect on the next frame, see [`GraphView::set_zoom`].
+    pub fn set_pan(&self, ui: &mut Ui, pan: Vec2) {
+        let mut meta = Metadata::load(ui);
+        meta.clear_camera_target();
+        self.apply_pan(pan, &mut meta);
+        meta.save(ui);
+    }
+
+    /// Pans the view so that the node is in the center of the widget, keeping the zoom.
+    /// Animated if [`SettingsNavigation::with_animation_duration_secs`] is set.
+    ///
+    /// Uses the widget rect of the last frame and takes effect on the next frame.
+    /// Does nothing if the node does not exist or the widget was not shown yet.
+    pub fn center_on_node(&self, ui: &mut Ui, idx: NodeIndex<Ix>) {
+        let mut meta = Metadata::load(ui);
+        self.center_meta_on_node(idx, &mut meta);
+        meta.save(ui);
+    }
+
+    fn center_meta_on_node(&self, idx: NodeIndex<Ix>, meta: &mut Metadata) {
+        let Some(n) = self.g.node(idx) else {
+            return;
+        };
+        if !meta.rect.is_positive() {
+            return;
+        }
+
+        let pan = meta.rect.center().to_vec2() - n.location().to_vec2() * meta.zoom;
+        self.move_camera(pan, meta.zoom, meta);
+    }
+
+    /// Moves the view to the given pan and zoom, animated if
+    /// [`SettingsNavigation::with_animation_duration_secs`] is set.
+    ///
+    /// The zoom is clamped to the zoom limits. Takes effect on the next frame.
+    pub fn animate_to(&self, ui: &mut Ui, pan: Vec2, zoom: f32) {
+        let mut meta = Metadata::load(ui);
+        self.move_camera(pan, zoom, &mut meta);
+        meta.save(ui);
+    }
+
+    /// Returns the bounding rect of all nodes including their sizes in canvas coordinates,
+    /// or `None` if the graph is empty.
+    ///
+    /// Bounds are computed from the current node locations, so they are up to date even after
+    /// nodes were moved since the last frame. Convert them to the screen with
+    /// [`Metadata::canvas_to_screen_pos`], e.g. to draw a minimap.
+    pub fn graph_bounds(&self) -> Option<Rect> {
+        nodes_bounds(self.g.nodes_iter().map(|(_, n)| n))
+    }
+
+    /// Returns the nodes which are at least partly inside `rect` together with their
+    /// locations, both in screen coordinates of the given metadata.
+    ///
+    /// Uses the same node bounds as the culling while drawing, and the smoothed locations the
+    /// nodes are rendered at. Pass the metadata of the last frame, loaded with
+    /// [`Metadata::load`], and e.g. its widget rect to place overlays only over visible nodes.
+    pub fn visible_nodes<'s>(
+        &'s self,
+        meta: &'s Metadata,
+        rect: Rect,
+    ) -> impl Iterator<Item = (NodeIndex<Ix>, Pos2)> + 's {
+        self.g.nodes_iter().filter_map(move |(idx, n)| {
+            let loc = meta
+                .rendered_location(idx.index())
+                .unwrap_or_else(|| n.location());
+            let size = Graph::<N, E, Ty, Ix, Dn, De>::display_bounds(n.display()).size();
+            let bounds = meta.canvas_to_screen_rect(Rect::from_center_size(loc, size));
+            rect.intersects(bounds)
+                .then(|| (idx, meta.canvas_to_screen_pos(loc)))
+        })
+    }
+
+    /// Zooms and pans so that the rect given in canvas coordinates fills the widget.
+    /// Animated if [`SettingsNavigation::with_animation_duration_secs`] is set.
+    ///
+    /// The zoom is clamped to the zoom limits. Uses the widget rect of the last frame and takes
+    /// effect on the next frame. Does nothing if the widget was not shown yet.
+    pub fn zoom_to_rect(&self, ui: &mut Ui, graph_rect: Rect) {
+        let mut meta = Metadata::load(ui);
+        if !meta.rect.is_positive() {
+            return;
+        }
+
+        let rect = meta.rect;
+        self.frame_bounds(&rect, graph_rect, 0., None, &mut meta);
+        meta.save(ui);
+    }
+
+    /// Prevents the widget from refitting the view to the graph on the next frame.
+    ///
+    /// Zoom and pan are stored in egui memory and are not tied to the graph, so the widget
+    /// keeps the viewport when the client replaces the graph, e.g. after loading a file.
+    /// The only exception is the very first frame which always fits the graph to the screen.
+    /// Call this before the first frame to start from the stored viewport instead.
+    ///
+    /// To intentionally frame the new graph call [`GraphView::reset_metadata`] after swapping
+    /// the graph instead.
+    pub fn keep_view(ui: &mut Ui) {
+        let mut meta = Metadata::load(ui);
+        meta.first_frame = false;
+        meta.save(ui);
+    }
+
+    /// Fits the view to the graph on the next frame, like on the first frame, while keeping
+    /// the rest of the metadata.
+    ///
+    /// Call this after replacing or loading the graph to frame it without resetting the
+    /// metadata with [`GraphView::reset_metadata`]. The fit waits until the graph has nodes.
+    /// A graph which is still empty on the first frame is fitted automatically once it gets
+    /// nodes, so asynchronously loaded graphs need no request.
+    pub fn request_fit(ui: &mut Ui) {
+        let mut meta = Metadata::load(ui);
+        meta.set_fit_pending(true);
+        meta.save(ui);
+    }
+
     /// Resets layout state
     pub fn reset_layout(ui: &mut Ui) {
         ui.data_mut(|data| {
@@ -199,7 +618,24 @@ where
         self
     }
 
-    fn sync_layout(&mut self, ui: &mut Ui) {
+    #[cfg(feature = "events")]
+    /// Returns events produced by the widget during the last frame.
+    ///
+    /// Call it right after adding the widget to the [`Ui`] to react to changes synchronously
+    /// without setting up a channel. Events are collected regardless of the channel supplied
+    /// with [`GraphView::with_events`] and are dropped at the start of the next frame.
+    pub fn take_last_events(&mut self) -> Vec<Event> {
+        std::mem::take(self.last_events.get_mut())
+    }
+
+    /// Runs the layout for the frame and returns whether it moved any node.
+    fn sync_layout(&mut self, ui: &mut Ui) -> bool {
+        let before = self
+            .g
+            .nodes_iter()
+            .map(|(_, n)| n.location())
+            .collect::<Vec<_>>();
+
         ui.data_mut(|data| {
             let state = data
                 .get_persisted::<S>(Id::new(KEY_LAYOUT))
@@ -209,18 +645,40 @@ where
 
             data.insert_persisted(Id::new(KEY_LAYOUT), layout.state());
         });
+
+        self.g.nodes_iter().map(|(_, n)| n.location()).ne(before)
+    }
+
+    /// Whether the view is moving on its own or with the pointer or keys held, so that the
+    /// next frame has to be drawn even without new input.
+    fn is_moving(&self, ui: &Ui, resp: &Response, meta: &Metadata) -> bool {
+        let keyboard_pan = self.settings_navigation.keyboard_navigation
+            && resp.has_focus()
+            && ui.input(|i| arrow_keys_direction(i) != Vec2::ZERO);
+
+        resp.dragged()
+            || keyboard_pan
+            || meta.camera_animating()
+            || (self.settings_navigation.pan_inertia && meta.gliding())
     }
 
     fn sync_state(&mut self, meta: &mut Metadata) {
+        self.drop_stale();
+
         let mut selected_nodes = Vec::new();
         let mut selected_edges = Vec::new();
         let mut dragged = None;
+        let mut hovered = None;
+        let mut hovered_edge = None;
 
         meta.reset_bounds();
         self.g.nodes_iter().for_each(|(idx, n)| {
             if n.dragged() {
                 dragged = Some(idx);
             }
+            if n.hovered() {
+                hovered = Some(idx);
+            }
             if n.selected() {
                 selected_nodes.push(idx);
             }
@@ -228,167 +686,661 @@ where
             meta.comp_iter_bounds(n);
         });
 
-        self.g.edges_iter().for_each(|(idx, e)| {
+        let incident = if self.settings_interaction.highlight_incident_edges {
+            selected_nodes
+                .iter()
+                .flat_map(|idx| self.g.incident_edges(*idx))
+                .collect::<HashSet<_>>()
+        } else {
+            HashSet::default()
+        };
+
+        let edges = self.g.edges_iter().map(|(idx, _)| idx).collect::<Vec<_>>();
+        for idx in edges {
+            let e = self.g.edge_mut(idx).unwrap();
+            e.set_highlighted(incident.contains(&idx));
             if e.selected() {
                 selected_edges.push(idx);
             }
-        });
+            if e.hovered() {
+                hovered_edge = Some(idx);
+            }
+        }
 
         self.g.set_selected_nodes(selected_nodes);
         self.g.set_selected_edges(selected_edges);
         self.g.set_dragged_node(dragged);
+        self.g.set_hovered_node(hovered);
+        self.g.set_hovered_edge(hovered_edge);
+
+        if let Some(idx) = self.g.secondary_clicked_node() {
+            if self.g.node(idx).is_none() {
+                self.g.set_secondary_clicked_node(None);
+            }
+        }
     }
 
-    /// Fits the graph to the screen if it is the first frame or
-    /// fit to screen setting is enabled;
-    fn handle_fit_to_screen(&self, r: &Response, meta: &mut Metadata) {
-        if !meta.first_frame && !self.settings_navigation.fit_to_screen_enabled {
-            return;
+    /// Handles nodes and edges removed from the graph by the client since the last frame.
+    ///
+    /// Selection and drag state are rebuilt from the graph elements in [`Self::sync_state`], so
+    /// indices of removed elements are dropped and never reach interactions. Removed elements
+    /// which were selected or dragged are reported as deselected or drag ended so that event
+    /// consumers can clean up their state as well.
+    #[allow(clippy::unused_self)]
+    fn drop_stale(&self) {
+        #[cfg(feature = "events")]
+        {
+            if let Some(idx) = self.g.dragged_node() {
+                if self.g.node(idx).is_none() {
+                    self.publish_event(Event::NodeDragEnd(PayloadNodeDragEnd { id: idx.index() }));
+                }
+            }
+            for idx in self.g.selected_nodes() {
+                if self.g.node(*idx).is_none() {
+                    self.publish_event(Event::NodeDeselect(PayloadNodeDeselect {
+                        id: idx.index(),
+                    }));
+                }
+            }
+            for idx in self.g.selected_edges() {
+                if self.g.edge(*idx).is_none() {
+                    self.publish_event(Event::EdgeDeselect(PayloadEdgeDeselect {
+                        id: idx.index(),
+                    }));
+                }
+            }
         }
+    }
+
+    /// Moves smoothed rendered locations of nodes towards their actual locations.
+    /// Returns whether they are still moving.
+    fn sync_rendered_locations(&self, ui: &Ui, meta: &mut Metadata) -> bool {
+        let Some(duration) = self.settings_style.position_lerp else {
+            meta.clear_rendered_locations();
+            return false;
+        };
 
-        self.fit_to_screen(&r.rect, meta);
+        let dt = ui.input(|i| i.stable_dt);
+        let t = if duration > 0. {
+            (dt / duration).min(1.)
+        } else {
+            1.
+        };
+        let nodes = self
+            .g
+            .nodes_iter()
+            .map(|(idx, n)| (idx.index(), n.location(), n.dragged()));
+        meta.lerp_locations(nodes, t)
     }
 
-    fn handle_click(&mut self, resp: &Response, meta: &mut Metadata) {
-        if !resp.clicked() && !resp.double_clicked() {
+    fn handle_fit_to_screen(&self, r: &Response, meta: &mut Metadata) {
+        if !self.fit_to_screen(&r.ctx, &r.rect, meta) {
             return;
         }
 
-        let clickable = self.settings_interaction.node_clicking_enabled
-            || self.settings_interaction.node_selection_enabled
-            || self.settings_interaction.node_selection_multi_enabled
-            || self.settings_interaction.edge_clicking_enabled
-            || self.settings_interaction.edge_selection_enabled
-            || self.settings_interaction.edge_selection_multi_enabled;
+        #[cfg(feature = "events")]
+        self.publish_event(Event::FitToScreen(PayloadFitToScreen {
+            new_zoom: meta.zoom,
+            new_pan: meta.pan.into(),
+        }));
+    }
 
-        if !(clickable) {
-            return;
+    /// Fits the graph to the screen if it is the first frame, a fit is pending or
+    /// fit to screen setting is enabled. Returns true if the view was fitted.
+    ///
+    /// The first fit is postponed while the graph is empty, so that graphs loaded after the
+    /// first frame are framed as soon as they have nodes.
+    fn fit_to_screen(&self, ctx: &Context, rect: &Rect, meta: &mut Metadata) -> bool {
+        let pending = meta.first_frame || meta.fit_pending();
+        if !pending && !self.settings_navigation.fit_to_screen_enabled {
+            return false;
         }
 
-        let Some(cursor_pos) = resp.hover_pos() else {
-            return;
+        // there is nothing to fit in an empty graph or an empty selection without fallback
+        let bounds = if self.settings_navigation.fit_to_selected {
+            self.selection_bounds(meta, self.settings_navigation.fit_selection_fallback)
+        } else {
+            meta.graph_bounds()
         };
-        let found_edge = self.g.edge_by_screen_pos(meta, cursor_pos);
-        let found_node = self.g.node_by_screen_pos(meta, cursor_pos);
-        if found_node.is_none() && found_edge.is_none() {
-            // click on empty space
-            let nodes_selectable = self.settings_interaction.node_selection_enabled
-                || self.settings_interaction.node_selection_multi_enabled;
-            if nodes_selectable {
-                self.deselect_all_nodes();
-            }
+        let Some(bounds) = bounds else {
+            meta.set_fit_pending(pending && self.g.node_count() == 0);
+            return false;
+        };
+        meta.set_fit_pending(false);
+        let bounds = self.bounds_with_labels(ctx, bounds);
+        self.fit_bounds(rect, bounds, meta);
+        true
+    }
 
-            let edges_selectable = self.settings_interaction.edge_selection_enabled
-                || self.settings_interaction.edge_selection_multi_enabled;
-            if edges_selectable {
-                self.deselect_all_edges();
-            }
+    /// Updates the hovered node and edge. Hover is cleared when the pointer leaves the widget.
+    fn handle_hover(&mut self, resp: &Response, meta: &Metadata) {
+        let node_hover = self.settings_interaction.node_hover_enabled;
+        let edge_hover = self.settings_interaction.edge_hover_enabled;
+        if !node_hover && !edge_hover {
             return;
         }
 
-        if let Some(idx) = found_node {
-            // first click of double click is handled by the lib as single click
-            // so if you double click a node it will handle it as single click at first
-            // and only after as double click
-            if resp.double_clicked() {
-                self.handle_node_double_click(idx);
-                return;
-            }
-            self.handle_node_click(idx);
-            return;
+        let pos = resp.hover_pos();
+        let node = pos.and_then(|pos| {
+            self.g
+                .node_by_screen_pos_ordered(meta, pos, self.settings_style.draw_order)
+        });
+        if node_hover {
+            self.set_hovered(node);
         }
-
-        if let Some(edge_idx) = found_edge {
-            self.handle_edge_click(edge_idx);
+        if edge_hover {
+            // nodes are drawn over edges, so they take precedence
+            let edge = pos
+                .filter(|_| node.is_none())
+                .and_then(|pos| self.edge_by_screen_pos(meta, pos));
+            self.set_hovered_edge(edge);
         }
     }
 
-    fn handle_node_double_click(&mut self, idx: NodeIndex<Ix>) {
-        if !self.settings_interaction.node_clicking_enabled {
+    fn set_hovered(&mut self, hovered: Option<NodeIndex<Ix>>) {
+        let prev = self.g.hovered_node();
+        if prev == hovered {
             return;
         }
 
-        if self.settings_interaction.node_clicking_enabled {
-            self.set_node_double_clicked(idx);
+        if let Some(idx) = prev {
+            if let Some(n) = self.g.node_mut(idx) {
+                n.set_hovered(false);
+
+                #[cfg(feature = "events")]
+                self.publish_event(Event::NodeHoverEnd(PayloadNodeHoverEnd { id: idx.index() }));
+            }
+        }
+        if let Some(idx) = hovered {
+            if let Some(n) = self.g.node_mut(idx) {
+                n.set_hovered(true);
+
+                #[cfg(feature = "events")]
+                self.publish_event(Event::NodeHoverStart(PayloadNodeHoverStart {
+                    id: idx.index(),
+                }));
+            }
         }
+        self.g.set_hovered_node(hovered);
     }
 
-    fn handle_node_click(&mut self, idx: NodeIndex<Ix>) {
-        if !self.settings_interaction.node_clicking_enabled
-            && !self.settings_interaction.node_selection_enabled
-        {
+    fn show_node_tooltip(&self, resp: &Response) {
+        let Some(tooltip) = self.node_tooltip else {
+            return;
+        };
+        let Some(idx) = self.g.hovered_node() else {
+            return;
+        };
+        let Some(text) = self.g.node(idx).and_then(|n| tooltip(idx, n.payload())) else {
+            return;
+        };
+
+        show_tooltip_at_pointer(&resp.ctx, resp.layer_id, resp.id.with(idx), |ui| {
+            ui.label(text);
+        });
+    }
+
+    /// Finds the edge at the screen position within the edge select tolerance.
+    fn edge_by_screen_pos(&self, meta: &Metadata, pos: Pos2) -> Option<EdgeIndex<Ix>> {
+        self.g.edge_by_screen_pos_with_tolerance(
+            meta,
+            pos,
+            self.settings_interaction.edge_select_tolerance,
+        )
+    }
+
+    fn set_hovered_edge(&mut self, hovered: Option<EdgeIndex<Ix>>) {
+        let prev = self.g.hovered_edge();
+        if prev == hovered {
             return;
         }
 
-        if self.settings_interaction.node_clicking_enabled {
-            self.set_node_clicked(idx);
+        if let Some(idx) = prev {
+            if let Some(e) = self.g.edge_mut(idx) {
+                e.set_hovered(false);
+
+                #[cfg(feature = "events")]
+                self.publish_event(Event::EdgeHoverEnd(PayloadEdgeHoverEnd { id: idx.index() }));
+            }
+        }
+        if let Some(idx) = hovered {
+            if let Some(e) = self.g.edge_mut(idx) {
+                e.set_hovered(true);
+
+                #[cfg(feature = "events")]
+                self.publish_event(Event::EdgeHoverStart(PayloadEdgeHoverStart {
+                    id: idx.index(),
+                }));
+            }
         }
+        self.g.set_hovered_edge(hovered);
+    }
 
-        if !self.settings_interaction.node_selection_enabled {
+    fn handle_click(&mut self, resp: &Response, meta: &mut Metadata) {
+        if !resp.clicked() && !resp.double_clicked() {
             return;
         }
 
-        let n = self.g.node(idx).unwrap();
-        if n.selected() {
-            self.deselect_node(idx);
+        let Some(cursor_pos) = resp.hover_pos() else {
             return;
-        }
+        };
 
-        if !self.settings_interaction.node_selection_multi_enabled {
-            self.deselect_all();
-        }
+        let double = resp.double_clicked();
+        let pos = cursor_pos.into();
+        self.record(if double {
+            Interaction::DoubleClick { pos }
+        } else {
+            Interaction::Click { pos }
+        });
 
-        self.select_node(idx);
-    }
+        self.click_at(cursor_pos, double, meta);
 
-    fn handle_edge_click(&mut self, idx: EdgeIndex<Ix>) {
-        if !self.settings_interaction.edge_clicking_enabled
-            && !self.settings_interaction.edge_selection_enabled
-        {
-            return;
+        if double && self.settings_navigation.double_click_fit {
+            self.fit_at(&resp.ctx, &resp.rect, cursor_pos, meta);
         }
+    }
 
-        if self.settings_interaction.edge_clicking_enabled {
-            self.set_edge_clicked(idx);
+    /// Frames the whole graph in the `rect` if nothing is found at the `cursor_pos`.
+    /// Returns whether the graph was framed.
+    fn fit_at(&self, ctx: &Context, rect: &Rect, cursor_pos: Pos2, meta: &mut Metadata) -> bool {
+        let found = self
+            .g
+            .node_by_screen_pos_ordered(meta, cursor_pos, self.settings_style.draw_order)
+            .is_some()
+            || self.edge_by_screen_pos(meta, cursor_pos).is_some();
+        if found {
+            return false;
         }
 
-        if !self.settings_interaction.edge_selection_enabled {
+        let Some(bounds) = meta.graph_bounds() else {
+            return false;
+        };
+        let bounds = self.bounds_with_labels(ctx, bounds);
+        self.fit_bounds(rect, bounds, meta);
+        true
+    }
+
+    fn handle_secondary_click(&mut self, resp: &Response, meta: &Metadata) {
+        if !self.settings_interaction.node_secondary_clicking_enabled || !resp.secondary_clicked() {
             return;
         }
 
-        let e = self.g.edge(idx).unwrap();
-        if e.selected() {
-            self.deselect_edge(idx);
+        let Some(cursor_pos) = resp.hover_pos() else {
             return;
-        }
+        };
+        let Some(idx) =
+            self.g
+                .node_by_screen_pos_ordered(meta, cursor_pos, self.settings_style.draw_order)
+        else {
+            return;
+        };
 
-        if !self.settings_interaction.edge_selection_multi_enabled {
-            self.deselect_all();
-        }
+        self.g.set_secondary_clicked_node(Some(idx));
 
-        self.select_edge(idx);
+        #[cfg(feature = "events")]
+        self.publish_event(Event::NodeSecondaryClick(PayloadNodeSecondaryClick {
+            id: idx.index(),
+        }));
     }
 
-    fn handle_node_drag(&mut self, resp: &Response, meta: &mut Metadata) {
-        if !self.settings_interaction.dragging_enabled {
+    /// Clears the selection when escape is pressed while the widget has focus,
+    /// like a click on empty space does.
+    fn handle_escape_key(&mut self, ui: &Ui, resp: &Response) {
+        if !resp.has_focus() || !ui.input(|i| i.key_pressed(Key::Escape)) {
             return;
         }
 
-        let node_hover_index = match resp.hover_pos() {
-            Some(hover_pos) => self.g.node_by_screen_pos(meta, hover_pos),
-            None => None,
+        self.clear_selection();
+    }
+
+    /// Selects all elements with ctrl + A and inverts the selection with ctrl + I,
+    /// see [`SettingsInteraction::with_select_all_shortcut`].
+    fn handle_select_all_key(&mut self, ui: &Ui, resp: &Response) {
+        if !self.settings_interaction.select_all_shortcut
+            || !self.settings_interaction.node_selection_multi_enabled
+            || !resp.has_focus()
+        {
+            return;
+        }
+
+        let (all, invert) = ui.input(|i| {
+            let command = i.modifiers.command;
+            (
+                command && i.key_pressed(Key::A),
+                command && i.key_pressed(Key::I),
+            )
+        });
+        if all {
+            self.select_all();
+        } else if invert {
+            self.invert_selection();
+        }
+    }
+
+    #[cfg(feature = "events")]
+    fn handle_delete_key(&mut self, ui: &Ui, resp: &Response) {
+        if !self.settings_interaction.deletion_enabled || !resp.has_focus() {
+            return;
+        }
+        if ui.input(|i| i.key_pressed(Key::Delete) || i.key_pressed(Key::Backspace)) {
+            self.request_deletion();
+        }
+    }
+
+    /// Publishes deletion requests for the selected edges followed by the selected nodes.
+    #[cfg(feature = "events")]
+    fn request_deletion(&self) {
+        for idx in self.selected_edges() {
+            let Some((source, target)) = self.g.edge_endpoints(idx) else {
+                continue;
+            };
+            self.publish_event(Event::EdgeDelete(PayloadEdgeDelete {
+                id: idx.index(),
+                source: source.index(),
+                target: target.index(),
+            }));
+        }
+
+        for idx in self.selected_nodes() {
+            self.publish_event(Event::NodeDelete(PayloadNodeDelete { id: idx.index() }));
+        }
+    }
+
+    fn click_at(&mut self, cursor_pos: Pos2, double: bool, meta: &Metadata) {
+        let clickable = self.settings_interaction.node_clicking_enabled
+            || self.settings_interaction.node_double_clicking_enabled
+            || self.settings_interaction.node_selection_enabled
+            || self.settings_interaction.node_selection_multi_enabled
+            || self.settings_interaction.edge_clicking_enabled
+            || self.settings_interaction.edge_selection_enabled
+            || self.settings_interaction.edge_selection_multi_enabled;
+
+        if !(clickable) {
+            return;
+        }
+
+        let found_edge = self.edge_by_screen_pos(meta, cursor_pos);
+        let found_node =
+            self.g
+                .node_by_screen_pos_ordered(meta, cursor_pos, self.settings_style.draw_order);
+        if found_node.is_none() && found_edge.is_none() {
+            // click on empty space
+            self.clear_selection();
+            return;
+        }
+
+        if let Some(idx) = found_node {
+            // first click of double click is handled by the lib as single click
+            // so if you double click a node it will handle it as single click at first
+            // and only after as double click, which does not toggle the selection again
+            if double {
+                self.handle_node_double_click(idx);
+                return;
+            }
+            self.handle_node_click(idx);
+            return;
+        }
+
+        if let Some(edge_idx) = found_edge {
+            self.handle_edge_click(edge_idx);
+        }
+    }
+
+    fn handle_node_double_click(&mut self, idx: NodeIndex<Ix>) {
+        if !self.settings_interaction.node_clicking_enabled
+            && !self.settings_interaction.node_double_clicking_enabled
+        {
+            return;
+        }
+
+        self.set_node_double_clicked(idx);
+    }
+
+    fn handle_node_click(&mut self, idx: NodeIndex<Ix>) {
+        if !self.settings_interaction.node_clicking_enabled
+            && !self.settings_interaction.node_selection_enabled
+        {
+            return;
+        }
+
+        if self.settings_interaction.node_clicking_enabled {
+            self.set_node_clicked(idx);
+        }
+
+        self.toggle_node_selection(idx);
+    }
+
+    fn handle_edge_click(&mut self, idx: EdgeIndex<Ix>) {
+        if !self.settings_interaction.edge_clicking_enabled
+            && !self.settings_interaction.edge_selection_enabled
+        {
+            return;
+        }
+
+        if self.settings_interaction.edge_clicking_enabled {
+            self.set_edge_clicked(idx);
+        }
+
+        if !self.settings_interaction.edge_selection_enabled {
+            return;
+        }
+
+        let e = self.g.edge(idx).unwrap();
+        if e.selected() {
+            self.deselect_edge(idx);
+            return;
+        }
+
+        if !self.settings_interaction.edge_selection_multi_enabled {
+            self.deselect_all();
+        }
+
+        self.select_edge(idx);
+    }
+
+    /// Selects nodes inside of a box dragged over empty space while shift is held.
+    /// The box takes precedence over panning.
+    fn handle_box_selection(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_interaction.node_selection_multi_enabled {
+            return;
+        }
+
+        if resp.drag_started_by(PointerButton::Primary) && ui.input(|i| i.modifiers.shift) {
+            let origin = ui.input(|i| i.pointer.press_origin());
+            if let Some(origin) = origin.filter(|pos| {
+                self.g
+                    .node_by_screen_pos_ordered(meta, *pos, self.settings_style.draw_order)
+                    .is_none()
+            }) {
+                meta.start_selection_box(meta.screen_to_canvas_pos(origin));
+            }
+        }
+
+        if let Some(pos) = resp.interact_pointer_pos() {
+            meta.update_selection_box(meta.screen_to_canvas_pos(pos));
+        }
+
+        if !resp.drag_stopped() {
+            return;
+        }
+        let Some(rect) = meta.take_selection_box() else {
+            return;
+        };
+        self.select_in_rect(rect);
+    }
+
+    fn handle_marquee_zoom(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_navigation.marquee_zoom {
+            return;
+        }
+
+        if resp.drag_started_by(PointerButton::Primary) && ui.input(|i| i.modifiers.command) {
+            let origin = ui.input(|i| i.pointer.press_origin());
+            if let Some(origin) = origin.filter(|pos| {
+                self.g
+                    .node_by_screen_pos_ordered(meta, *pos, self.settings_style.draw_order)
+                    .is_none()
+            }) {
+                meta.start_zoom_box(meta.screen_to_canvas_pos(origin));
+            }
+        }
+
+        if let Some(pos) = resp.interact_pointer_pos() {
+            meta.update_zoom_box(meta.screen_to_canvas_pos(pos));
+        }
+
+        if !resp.drag_stopped() {
+            return;
+        }
+        let Some(rect) = meta.take_zoom_box() else {
+            return;
+        };
+        // a box too small on the screen is rather a slip of the pointer
+        let size = meta.canvas_to_screen_size(rect.width().max(rect.height()));
+        if size < MIN_ZOOM_BOX_SIZE {
+            return;
+        }
+        self.frame_bounds(&resp.rect, rect, 0., None, meta);
+    }
+
+    /// Returns the minimap of the graph for the widget `rect` if it is enabled and the graph
+    /// is not empty.
+    fn minimap(&self, rect: Rect, meta: &Metadata) -> Option<Minimap> {
+        if !self.settings_navigation.show_minimap {
+            return None;
+        }
+
+        let nav = &self.settings_navigation;
+        meta.graph_bounds()
+            .map(|bounds| Minimap::new(rect, nav.minimap_corner, nav.minimap_size, bounds))
+    }
+
+    /// Centers the view on the graph position under the pointer while it is pressed in the
+    /// minimap. The press has to start in the minimap, dragging out of it keeps moving the view.
+    fn handle_minimap(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        let Some(minimap) = self.minimap(resp.rect, meta) else {
+            meta.set_minimap_drag(false);
+            return;
+        };
+        let Some(pos) = resp.interact_pointer_pos() else {
+            meta.set_minimap_drag(false);
+            return;
+        };
+
+        if ui.input(|i| i.pointer.primary_pressed()) {
+            meta.set_minimap_drag(minimap.frame().contains(pos));
+        }
+        if !meta.minimap_drag() {
+            return;
+        }
+
+        let target = minimap.minimap_to_canvas_pos(minimap.frame().clamp(pos));
+        let delta = resp.rect.center() - meta.canvas_to_screen_pos(target);
+        self.record(Interaction::Pan {
+            delta: delta.into(),
+        });
+        meta.clear_camera_target();
+        meta.stop_pan_velocity();
+        self.apply_pan(meta.pan + delta, meta);
+    }
+
+    /// Adds nodes located inside of the `rect` in canvas coordinates to the selection.
+    fn select_in_rect(&mut self, rect: Rect) {
+        let inside = self
+            .g
+            .nodes_iter()
+            .filter(|(_, n)| !n.selected() && rect.contains(n.location()))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        for idx in inside {
+            self.select_node(idx);
+        }
+
+        self.refresh_selection();
+    }
+
+    fn handle_edge_creation(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_interaction.edge_creation_enabled {
+            return;
+        }
+
+        if meta.edge_draft().is_none()
+            && self.g.dragged_node().is_none()
+            && resp.is_pointer_button_down_on
+            && ui.input(|i| i.modifiers.alt && i.pointer.primary_down())
+        {
+            let source = resp.hover_pos().and_then(|pos| {
+                self.g
+                    .node_by_screen_pos_ordered(meta, pos, self.settings_style.draw_order)
+                    .map(|idx| (idx, pos))
+            });
+            if let Some((idx, pos)) = source {
+                meta.start_edge_draft(idx.index(), meta.screen_to_canvas_pos(pos));
+            }
+        }
+
+        if let Some(pos) = resp.hover_pos() {
+            meta.update_edge_draft(meta.screen_to_canvas_pos(pos));
+        }
+
+        if !resp.is_pointer_button_down_on {
+            self.finish_edge_draft(meta);
+        }
+    }
+
+    /// Ends the edge being created and requests it if it ends on a node other than the source.
+    fn finish_edge_draft(&self, meta: &mut Metadata) {
+        let Some((source, end)) = meta.take_edge_draft() else {
+            return;
+        };
+        let Some(target) = self.g.node_by_screen_pos_ordered(
+            meta,
+            meta.canvas_to_screen_pos(end),
+            self.settings_style.draw_order,
+        ) else {
+            return;
+        };
+        if target.index() == source {
+            return;
+        }
+
+        #[cfg(feature = "events")]
+        self.publish_event(Event::EdgeCreate(PayloadEdgeCreate {
+            source,
+            target: target.index(),
+        }));
+    }
+
+    fn handle_node_drag(&mut self, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_interaction.dragging_enabled {
+            return;
+        }
+
+        let node_hover_index = match resp.hover_pos() {
+            Some(hover_pos) => {
+                self.g
+                    .node_by_screen_pos_ordered(meta, hover_pos, self.settings_style.draw_order)
+            }
+            None => None,
         };
-        if resp.is_pointer_button_down_on && node_hover_index.is_some() {
-            // self.g.node(node_hover_index);
-            if self.g.dragged_node().is_none() {
-                self.set_drag_start(node_hover_index.unwrap());
+        // dragging from a node creates an edge instead of moving the node
+        if resp.is_pointer_button_down_on
+            && node_hover_index.is_some()
+            && meta.edge_draft().is_none()
+        {
+            if let (None, Some(hovered)) = (self.g.dragged_node(), node_hover_index) {
+                self.set_drag_start(hovered);
                 self.g.set_dragged_node(node_hover_index);
+                if let Some(pos) = resp.interact_pointer_pos() {
+                    self.start_node_drag(hovered, meta.screen_to_canvas_pos(pos), meta);
+                }
             }
         } else if !resp.is_pointer_button_down_on {
             match self.g.dragged_node() {
                 Some(dragged_node) => {
                     self.set_drag_end(dragged_node);
                     self.g.set_dragged_node(None);
+                    meta.clear_node_drag();
                 }
                 None => (),
             };
@@ -402,44 +1354,74 @@ where
         }
 
         // handle mouse drag
-        if resp.dragged()
-            && self.g.dragged_node().is_some()
-            && (resp.drag_delta().x.abs() > 0. || resp.drag_delta().y.abs() > 0.)
-        {
-            let n_idx_dragged = self.g.dragged_node().unwrap();
-            let delta_in_graph_coords = resp.drag_delta() / meta.zoom;
-            self.move_node(n_idx_dragged, delta_in_graph_coords);
+        if resp.dragged() && (resp.drag_delta().x.abs() > 0. || resp.drag_delta().y.abs() > 0.) {
+            if let Some(n_idx_dragged) = self.g.dragged_node() {
+                if let Some(pos) = resp.hover_pos() {
+                    self.record(Interaction::NodeDrag {
+                        from: (pos - resp.drag_delta()).into(),
+                        delta: resp.drag_delta().into(),
+                    });
+                }
+                match resp.interact_pointer_pos() {
+                    Some(pos) if meta.drag_origin().is_some() => {
+                        self.drag_to(meta.screen_to_canvas_pos(pos), meta);
+                    }
+                    _ => self.move_dragged(n_idx_dragged, resp.drag_delta() / meta.zoom),
+                }
+            }
         }
 
-        // compensate movement of the node which is not caused by dragging
-        if let Some(n_idx_dragged) = self.g.dragged_node() {
+        // compensate movement of the node which is not caused by dragging, not needed when
+        // locations are derived from the drag origin
+        if let (Some(n_idx_dragged), None) = (self.g.dragged_node(), meta.drag_origin()) {
             if let Some(mouse_pos) = resp.hover_pos() {
                 if let Some(node) = self.g.node(n_idx_dragged) {
                     let node_pos = node.location() * meta.zoom + meta.pan;
                     let delta = mouse_pos - node_pos;
 
-                    self.move_node(n_idx_dragged, delta / meta.zoom);
+                    self.move_dragged(n_idx_dragged, delta / meta.zoom);
                 }
             }
         }
 
-        if resp.drag_stopped() && self.g.dragged_node().is_some() {
-            let n_idx = self.g.dragged_node().unwrap();
-            self.set_drag_end(n_idx);
+        if resp.drag_stopped() {
+            if let Some(n_idx) = self.g.dragged_node() {
+                self.set_drag_end(n_idx);
+            }
         }
     }
 
-    fn fit_to_screen(&self, rect: &Rect, meta: &mut Metadata) {
+    /// Zooms and pans so that `bounds` given in canvas coordinates fill the `rect`.
+    fn fit_bounds(&self, rect: &Rect, bounds: Rect, meta: &mut Metadata) {
+        self.frame_bounds(
+            rect,
+            bounds,
+            self.settings_navigation.screen_padding,
+            self.settings_navigation.max_fit_zoom,
+            meta,
+        );
+    }
+
+    /// Zooms and pans so that `bounds` with the relative `padding` fill the `rect`,
+    /// zooming in at most to the `max_zoom`.
+    fn frame_bounds(
+        &self,
+        rect: &Rect,
+        bounds: Rect,
+        padding: f32,
+        max_zoom: Option<f32>,
+        meta: &mut Metadata,
+    ) {
         // calculate graph dimensions with decorative padding
-        let bounds = meta.graph_bounds();
         let mut diag = bounds.max - bounds.min;
 
-        // if the graph is empty or consists from one node, use a default size
-        if diag == Vec2::ZERO {
+        // if the graph consists from one point, use a default size. If only one of the
+        // dimensions is zero, the zoom is determined by the other one
+        if diag.x <= 0. && diag.y <= 0. {
             diag = Vec2::new(1., 100.);
         }
 
-        let graph_size = diag * (1. + self.settings_navigation.screen_padding);
+        let graph_size = diag * (1. + padding);
         let (width, height) = (graph_size.x, graph_size.y);
 
         // calculate canvas dimensions
@@ -451,18 +1433,56 @@ where
         let zoom_y = canvas_height / height;
 
         // choose the minimum of the two zoom factors to avoid distortion
-        let new_zoom = zoom_x.min(zoom_y);
+        let mut new_zoom = zoom_x.min(zoom_y);
+        if let Some(max_zoom) = max_zoom {
+            new_zoom = new_zoom.min(max_zoom);
+        }
 
-        // calculate the zoom delta and call handle_zoom to adjust the zoom factor
-        let zoom_delta = new_zoom / meta.zoom - 1.0;
-        self.zoom(rect, zoom_delta, None, meta);
+        let new_zoom = new_zoom.clamp(
+            self.settings_navigation.zoom_min,
+            self.settings_navigation.zoom_max,
+        );
 
         // calculate the center of the graph and the canvas
         let graph_center = (bounds.min.to_vec2() + bounds.max.to_vec2()) / 2.0;
 
         // adjust the pan value to align the centers of the graph and the canvas
         let new_pan = rect.center().to_vec2() - graph_center * new_zoom;
-        self.set_pan(new_pan, meta);
+        self.move_camera(new_pan, new_zoom, meta);
+    }
+
+    /// Sets pan and zoom, or makes them the target of an animation if animations are enabled.
+    /// The first frame is never animated.
+    fn move_camera(&self, pan: Vec2, zoom: f32, meta: &mut Metadata) {
+        let zoom = zoom.clamp(
+            self.settings_navigation.zoom_min,
+            self.settings_navigation.zoom_max,
+        );
+
+        if self.settings_navigation.animation_duration_secs > 0. && !meta.first_frame {
+            meta.set_camera_target(pan, zoom);
+            return;
+        }
+
+        meta.clear_camera_target();
+        self.apply_zoom(zoom, meta);
+        self.apply_pan(pan, meta);
+    }
+
+    /// Moves pan and zoom towards the target of the running animation.
+    fn animate_camera(&self, ui: &Ui, meta: &mut Metadata) {
+        let duration = self.settings_navigation.animation_duration_secs;
+        let t = if duration > 0. {
+            (ui.input(|i| i.stable_dt) / duration).min(1.)
+        } else {
+            1.
+        };
+
+        let Some((pan, zoom)) = meta.step_camera(t) else {
+            return;
+        };
+        self.apply_zoom(zoom, meta);
+        self.apply_pan(pan, meta);
     }
 
     fn handle_navigation(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
@@ -470,56 +1490,227 @@ where
             meta.pan += resp.rect.left_top() - meta.top_left;
         }
         meta.top_left = resp.rect.left_top();
+        meta.rect = resp.rect;
 
+        self.handle_focus(ui, resp);
         self.handle_zoom(ui, resp, meta);
-        self.handle_pan(resp, meta);
+        self.handle_pan(ui, resp, meta);
+        self.handle_keyboard_pan(ui, resp, meta);
+        self.handle_fit_key(ui, resp, meta);
+    }
+
+    /// Focuses the widget when it is interacted with, so that it receives key presses.
+    fn handle_focus(&self, ui: &Ui, resp: &Response) {
+        let keyboard_navigation = self.settings_navigation.keyboard_navigation;
+        let selectable = self.nodes_selectable() || self.edges_selectable();
+        if self.settings_navigation.fit_key.is_none()
+            && !keyboard_navigation
+            && !self.settings_interaction.deletion_enabled
+            && !selectable
+        {
+            return;
+        }
+
+        if resp.clicked() || resp.drag_started() {
+            resp.request_focus();
+        }
+        if keyboard_navigation || selectable {
+            // otherwise arrow keys move the focus to other widgets and escape drops the focus
+            // before the widget sees the key
+            let filter = EventFilter {
+                horizontal_arrows: keyboard_navigation,
+                vertical_arrows: keyboard_navigation,
+                escape: selectable,
+                ..Default::default()
+            };
+            ui.memory_mut(|m| m.set_focus_lock_filter(resp.id, filter));
+        }
     }
 
-    fn handle_zoom(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+    /// Pans the view with the held arrow keys when the widget has focus.
+    fn handle_keyboard_pan(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        if !self.settings_navigation.keyboard_navigation || !resp.has_focus() {
+            return;
+        }
+
+        let (direction, dt) = ui.input(|i| (arrow_keys_direction(i), i.stable_dt));
+        if direction == Vec2::ZERO {
+            return;
+        }
+
+        let delta = direction * self.settings_navigation.keyboard_pan_step * dt;
+        let new_pan = self.bounded_pan(&resp.rect, meta.pan + delta, meta.zoom, meta);
+        self.record(Interaction::Pan {
+            delta: (new_pan - meta.pan).into(),
+        });
+        meta.clear_camera_target();
+        self.apply_pan(new_pan, meta);
+    }
+
+    /// Frames the selected nodes, or the whole graph if nothing is selected,
+    /// when the fit key is pressed while the widget has focus.
+    fn handle_fit_key(&self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
+        let Some(key) = self.settings_navigation.fit_key else {
+            return;
+        };
+
+        if !resp.has_focus() || !ui.input(|i| i.key_pressed(key)) {
+            return;
+        }
+
+        let Some(bounds) = self.selection_bounds(meta, true) else {
+            return;
+        };
+        let bounds = self.bounds_with_labels(&resp.ctx, bounds);
+        self.fit_bounds(&resp.rect, bounds, meta);
+    }
+
+    /// Extends `bounds` with the labels of the nodes inside of them,
+    /// see [`SettingsNavigation::with_fit_to_labels`].
+    fn bounds_with_labels(&self, ctx: &Context, bounds: Rect) -> Rect {
+        if !self.settings_navigation.fit_to_labels {
+            return bounds;
+        }
+
+        let nodes = self
+            .g
+            .nodes_iter()
+            .map(|(_, n)| n)
+            .filter(|n| bounds.contains(n.location()));
+        ctx.fonts(|f| labels_bounds(f, nodes))
+            .map_or(bounds, |labels| bounds.union(labels))
+    }
+
+    /// Returns bounds of the selected nodes. If nothing is selected returns bounds
+    /// of the whole graph with `fallback`, otherwise `None`.
+    fn selection_bounds(&self, meta: &Metadata, fallback: bool) -> Option<Rect> {
+        let selected = self
+            .g
+            .selected_nodes()
+            .iter()
+            .filter_map(|idx| self.g.node(*idx));
+        nodes_bounds(selected).or_else(|| meta.graph_bounds().filter(|_| fallback))
+    }
+
+    fn handle_zoom(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
         if !self.settings_navigation.zoom_and_pan_enabled {
             return;
         }
 
-        ui.input(|i| {
-            let delta = i.zoom_delta();
-            if delta == 1. {
-                return;
-            }
+        let (delta, pinch, center) =
+            ui.input(|i| (i.zoom_delta(), is_pinch(i), i.pointer.hover_pos()));
+        if delta == 1. {
+            return;
+        }
 
-            let step = self.settings_navigation.zoom_speed * (delta - 1.).signum();
-            self.zoom(&resp.rect, step, i.pointer.hover_pos(), meta);
+        let step = zoom_step(
+            delta,
+            pinch,
+            self.settings_navigation.zoom_speed,
+            self.settings_navigation.invert_zoom,
+        );
+        self.record(Interaction::Zoom {
+            delta: step,
+            center: center.map(Into::into),
         });
+        // user input takes over from a running animation
+        meta.clear_camera_target();
+        self.zoom_by(&resp.rect, step, center, meta);
     }
 
-    fn handle_pan(&self, resp: &Response, meta: &mut Metadata) {
+    fn handle_pan(&mut self, ui: &Ui, resp: &Response, meta: &mut Metadata) {
         if !self.settings_navigation.zoom_and_pan_enabled {
             return;
         }
 
-        if (resp.dragged_by(PointerButton::Middle) || resp.dragged_by(PointerButton::Primary))
+        let dt = ui.input(|i| i.stable_dt);
+        if resp.drag_started() || (resp.is_pointer_button_down_on && !resp.dragged()) {
+            // grabbing the view stops gliding, only the drag keeps it moving
+            meta.stop_pan_velocity();
+        } else if self.settings_navigation.pan_inertia {
+            if let Some(delta) = meta.step_pan_inertia(dt) {
+                let new_pan = self.bounded_pan(&resp.rect, meta.pan + delta, meta.zoom, meta);
+                if new_pan != meta.pan + delta {
+                    // gliding against the bounds would only push the view back every frame
+                    meta.stop_pan_velocity();
+                }
+                self.record(Interaction::Pan {
+                    delta: (new_pan - meta.pan).into(),
+                });
+                self.apply_pan(new_pan, meta);
+            }
+        }
+
+        if self.pan_dragged(resp)
             && self.g.dragged_node().is_none()
+            && meta.selection_box().is_none()
+            && meta.zoom_box().is_none()
+            && meta.edge_draft().is_none()
+            && !meta.minimap_drag()
         {
-            let new_pan = meta.pan + resp.drag_delta();
-            self.set_pan(new_pan, meta);
+            let delta = resp.drag_delta() * self.settings_navigation.pan_sensitivity;
+            let new_pan = self.bounded_pan(&resp.rect, meta.pan + delta, meta.zoom, meta);
+            let delta = new_pan - meta.pan;
+            self.record(Interaction::Pan {
+                delta: delta.into(),
+            });
+            meta.clear_camera_target();
+            meta.track_pan_velocity(delta, dt);
+            self.apply_pan(new_pan, meta);
         }
     }
 
+    /// Returns true if the widget is dragged by the pan button or the middle button.
+    fn pan_dragged(&self, resp: &Response) -> bool {
+        resp.dragged_by(self.settings_navigation.pan_button)
+            || resp.dragged_by(PointerButton::Middle)
+    }
+
     /// Zooms the graph by the given delta. It also compensates with pan to keep the zoom center in the same place.
-    fn zoom(&self, rect: &Rect, delta: f32, zoom_center: Option<Pos2>, meta: &mut Metadata) {
+    ///
+    /// The resulting zoom is clamped to the zoom limits of the navigation settings.
+    fn zoom_by(&self, rect: &Rect, delta: f32, zoom_center: Option<Pos2>, meta: &mut Metadata) {
         let center_pos = zoom_center.unwrap_or(rect.center()).to_vec2();
         let graph_center_pos = (center_pos - meta.pan) / meta.zoom;
         let factor = 1. + delta;
-        let new_zoom = meta.zoom * factor;
+        let new_zoom = (meta.zoom * factor).clamp(
+            self.settings_navigation.zoom_min,
+            self.settings_navigation.zoom_max,
+        );
 
         let pan_delta = graph_center_pos * meta.zoom - graph_center_pos * new_zoom;
         let new_pan = meta.pan + pan_delta;
 
-        self.set_pan(new_pan, meta);
-        self.set_zoom(new_zoom, meta);
+        let new_pan = self.bounded_pan(rect, new_pan, new_zoom, meta);
+
+        self.apply_pan(new_pan, meta);
+        self.apply_zoom(new_zoom, meta);
+    }
+
+    /// Clamps the `pan` with the pan bounds of the navigation settings, so that a part of
+    /// the graph stays in the widget `rect` at the given `zoom`.
+    ///
+    /// Returns the `pan` as is without pan bounds or for an empty graph.
+    fn bounded_pan(&self, rect: &Rect, pan: Vec2, zoom: f32, meta: &Metadata) -> Vec2 {
+        let Some(margin) = self.settings_navigation.pan_bounds else {
+            return pan;
+        };
+        let Some(bounds) = meta.graph_bounds() else {
+            return pan;
+        };
+
+        let min = bounds.min.to_vec2() * zoom;
+        let max = bounds.max.to_vec2() * zoom;
+        Vec2::new(
+            clamp_pan_axis(pan.x, min.x, max.x, rect.x_range(), margin),
+            clamp_pan_axis(pan.y, min.y, max.y, rect.y_range(), margin),
+        )
     }
 
     fn select_node(&mut self, idx: NodeIndex<Ix>) {
-        let n = self.g.node_mut(idx).unwrap();
+        let Some(n) = self.g.node_mut(idx) else {
+            return;
+        };
         n.set_selected(true);
 
         #[cfg(feature = "events")]
@@ -527,7 +1718,9 @@ where
     }
 
     fn deselect_node(&mut self, idx: NodeIndex<Ix>) {
-        let n = self.g.node_mut(idx).unwrap();
+        let Some(n) = self.g.node_mut(idx) else {
+            return;
+        };
         n.set_selected(false);
 
         #[cfg(feature = "events")]
@@ -555,7 +1748,9 @@ where
     }
 
     fn select_edge(&mut self, idx: EdgeIndex<Ix>) {
-        let e = self.g.edge_mut(idx).unwrap();
+        let Some(e) = self.g.edge_mut(idx) else {
+            return;
+        };
         e.set_selected(true);
 
         #[cfg(feature = "events")]
@@ -563,13 +1758,114 @@ where
     }
 
     fn deselect_edge(&mut self, idx: EdgeIndex<Ix>) {
-        let e = self.g.edge_mut(idx).unwrap();
+        let Some(e) = self.g.edge_mut(idx) else {
+            return;
+        };
         e.set_selected(false);
 
         #[cfg(feature = "events")]
         self.publish_event(Event::EdgeDeselect(PayloadEdgeDeselect { id: idx.index() }));
     }
 
+    /// Updates selected nodes and edges cached in the graph from the selection state of its
+    /// elements, so that subsequent selection changes within the same frame see them.
+    fn refresh_selection(&mut self) {
+        let selected_nodes = self
+            .g
+            .nodes_iter()
+            .filter(|(_, n)| n.selected())
+            .map(|(idx, _)| idx)
+            .collect();
+        let selected_edges = self
+            .g
+            .edges_iter()
+            .filter(|(_, e)| e.selected())
+            .map(|(idx, _)| idx)
+            .collect();
+        self.g.set_selected_nodes(selected_nodes);
+        self.g.set_selected_edges(selected_edges);
+    }
+
+    fn nodes_selectable(&self) -> bool {
+        self.settings_interaction.node_selection_enabled
+            || self.settings_interaction.node_selection_multi_enabled
+    }
+
+    fn edges_selectable(&self) -> bool {
+        self.settings_interaction.edge_selection_enabled
+            || self.settings_interaction.edge_selection_multi_enabled
+    }
+
+    /// Deselects nodes and edges if their selection is enabled.
+    fn clear_selection(&mut self) {
+        if self.nodes_selectable() {
+            self.deselect_all_nodes();
+        }
+        if self.edges_selectable() {
+            self.deselect_all_edges();
+        }
+    }
+
+    /// Selects all nodes, and all edges if their multiselection is enabled.
+    fn select_all(&mut self) {
+        let nodes = self
+            .g
+            .nodes_iter()
+            .filter(|(_, n)| !n.selected())
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        for idx in nodes {
+            self.select_node(idx);
+        }
+
+        if self.settings_interaction.edge_selection_multi_enabled {
+            let edges = self
+                .g
+                .edges_iter()
+                .filter(|(_, e)| !e.selected())
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>();
+            for idx in edges {
+                self.select_edge(idx);
+            }
+        }
+
+        self.refresh_selection();
+    }
+
+    /// Inverts the selection of nodes, and of edges if their multiselection is enabled.
+    fn invert_selection(&mut self) {
+        let nodes = self
+            .g
+            .nodes_iter()
+            .map(|(idx, n)| (idx, n.selected()))
+            .collect::<Vec<_>>();
+        for (idx, selected) in nodes {
+            if selected {
+                self.deselect_node(idx);
+            } else {
+                self.select_node(idx);
+            }
+        }
+
+        if self.settings_interaction.edge_selection_multi_enabled {
+            let edges = self
+                .g
+                .edges_iter()
+                .map(|(idx, e)| (idx, e.selected()))
+                .collect::<Vec<_>>();
+            for (idx, selected) in edges {
+                if selected {
+                    self.deselect_edge(idx);
+                } else {
+                    self.select_edge(idx);
+                }
+            }
+        }
+
+        self.refresh_selection();
+    }
+
     /// Deselects all nodes AND edges.
     fn deselect_all(&mut self) {
         self.deselect_all_nodes();
@@ -590,8 +1886,158 @@ where
         }
     }
 
+    /// Drags the node found at `from` by `delta` given in screen coordinates.
+    /// Moves the node dragged by the replayed `drag` if the drag frame continues it, otherwise
+    /// ends it and starts a new drag of the node found at `from`.
+    fn replay_drag(
+        &mut self,
+        drag: Option<(NodeIndex<Ix>, Pos2)>,
+        from: Pos2,
+        delta: Vec2,
+        meta: &Metadata,
+    ) -> Option<(NodeIndex<Ix>, Pos2)> {
+        let dragged = match drag {
+            Some((idx, end)) if end.distance(from) < DRAG_CONTINUITY => Some(idx),
+            _ => {
+                self.end_replayed_drag(drag);
+                self.start_drag_at(from, meta)
+            }
+        }?;
+
+        self.move_dragged(dragged, delta / meta.zoom);
+        Some((dragged, from + delta))
+    }
+
+    /// Starts a replayed drag of the node found at `from`.
+    fn start_drag_at(&mut self, from: Pos2, meta: &Metadata) -> Option<NodeIndex<Ix>> {
+        if !self.settings_interaction.dragging_enabled {
+            return None;
+        }
+
+        let idx = self
+            .g
+            .node_by_screen_pos_ordered(meta, from, self.settings_style.draw_order)?;
+        self.set_drag_start(idx);
+        self.g.set_dragged_node(Some(idx));
+        Some(idx)
+    }
+
+    fn end_replayed_drag(&mut self, drag: Option<(NodeIndex<Ix>, Pos2)>) {
+        let Some((idx, _)) = drag else {
+            return;
+        };
+
+        self.set_drag_end(idx);
+        self.g.set_dragged_node(None);
+    }
+
+    /// Updates displays of all nodes and edges from their current properties.
+    fn sync_displays(&mut self) {
+        let nodes = self.g.nodes_iter().map(|(idx, _)| idx).collect::<Vec<_>>();
+        for idx in nodes {
+            let n = self.g.node_mut(idx).unwrap();
+            let props = n.props().clone();
+            n.display_mut().update(&props);
+        }
+
+        let edges = self.g.edges_iter().map(|(idx, _)| idx).collect::<Vec<_>>();
+        for idx in edges {
+            let e = self.g.edge_mut(idx).unwrap();
+            let props = e.props().clone();
+            e.display_mut().update(&props);
+        }
+    }
+
+    fn record(&mut self, interaction: Interaction) {
+        if let Some(log) = &mut self.interaction_log {
+            log.push(interaction);
+        }
+    }
+
+    /// Moves the dragged node together with the rest of its group by the same delta.
+    fn move_dragged(&mut self, dragged: NodeIndex<Ix>, delta: Vec2) {
+        for idx in self.drag_group(dragged) {
+            let delta = match (self.settings_interaction.snap_to_grid, self.g.node(idx)) {
+                // only stored locations are snapped, dragging follows the pointer
+                (Some(cell), Some(n)) => snap_to_grid(n.location() + delta, cell) - n.location(),
+                _ => delta,
+            };
+            self.move_node(idx, delta);
+        }
+    }
+
+    /// Remembers the pointer and the locations of the nodes moved with the `dragged` node at
+    /// the start of a drag, see [`Self::drag_to`].
+    fn start_node_drag(&self, dragged: NodeIndex<Ix>, pointer: Pos2, meta: &mut Metadata) {
+        let nodes = self
+            .drag_group(dragged)
+            .into_iter()
+            .filter_map(|idx| Some((idx.index(), self.g.node(idx)?.location())))
+            .collect();
+        meta.start_node_drag(pointer, nodes);
+    }
+
+    /// Moves the dragged nodes to their locations at the start of the drag offset by the
+    /// pointer movement since then, given the `pointer` in canvas coordinates.
+    ///
+    /// Locations are derived from the drag origin every frame instead of accumulating
+    /// per-frame deltas, so long drags do not drift away from the pointer.
+    fn drag_to(&mut self, pointer: Pos2, meta: &Metadata) {
+        let Some((origin, nodes)) = meta.drag_origin() else {
+            return;
+        };
+
+        let offset = pointer - *origin;
+        for (idx, start) in nodes {
+            let idx = NodeIndex::new(*idx);
+            let Some(n) = self.g.node(idx) else {
+                continue;
+            };
+            let mut target = *start + offset;
+            if let Some(cell) = self.settings_interaction.snap_to_grid {
+                target = snap_to_grid(target, cell);
+            }
+            let delta = target - n.location();
+            if delta != Vec2::ZERO {
+                self.move_node(idx, delta);
+            }
+        }
+    }
+
+    /// Returns nodes which are moved when the `dragged` node is dragged.
+    ///
+    /// If the dragged node is part of a multiselection, the whole selection is moved rigidly.
+    /// Otherwise, or if the selection exceeds [`SettingsInteraction::with_max_group_drag`],
+    /// only the dragged node is moved. Every node is present in the result exactly once,
+    /// so the drag delta is never applied twice to the same node.
+    fn drag_group(&self, dragged: NodeIndex<Ix>) -> Vec<NodeIndex<Ix>> {
+        // `Option::is_none_or` would raise the minimum supported Rust version to 1.82
+        let within_limit = match self.settings_interaction.max_group_drag {
+            Some(max) => self.g.selected_nodes().len() <= max,
+            None => true,
+        };
+        let group_drag = self.settings_interaction.node_selection_multi_enabled
+            && within_limit
+            && self.g.node(dragged).is_some_and(Node::selected);
+        if !group_drag {
+            return vec![dragged];
+        }
+
+        let mut group = vec![dragged];
+        group.extend(
+            self.g
+                .selected_nodes()
+                .iter()
+                .copied()
+                .filter(|idx| *idx != dragged),
+        );
+        group
+    }
+
     fn move_node(&mut self, idx: NodeIndex<Ix>, delta: Vec2) {
-        let n = self.g.node_mut(idx).unwrap();
+        let Some(n) = self.g.node_mut(idx).filter(|n| !n.pinned()) else {
+            return;
+        };
         let new_loc = n.location() + delta;
         n.set_location(new_loc);
 
@@ -604,7 +2050,9 @@ where
     }
 
     fn set_drag_start(&mut self, idx: NodeIndex<Ix>) {
-        let n = self.g.node_mut(idx).unwrap();
+        let Some(n) = self.g.node_mut(idx) else {
+            return;
+        };
         n.set_dragged(true);
 
         #[cfg(feature = "events")]
@@ -614,7 +2062,9 @@ where
     }
 
     fn set_drag_end(&mut self, idx: NodeIndex<Ix>) {
-        let n = self.g.node_mut(idx).unwrap();
+        let Some(n) = self.g.node_mut(idx) else {
+            return;
+        };
         n.set_dragged(false);
 
         #[cfg(feature = "events")]
@@ -622,7 +2072,7 @@ where
     }
 
     #[allow(unused_variables, clippy::unused_self)]
-    fn set_pan(&self, new_pan: Vec2, meta: &mut Metadata) {
+    fn apply_pan(&self, new_pan: Vec2, meta: &mut Metadata) {
         let diff = new_pan - meta.pan;
         meta.pan = new_pan;
 
@@ -634,7 +2084,7 @@ where
     }
 
     #[allow(unused_variables, clippy::unused_self)]
-    fn set_zoom(&self, new_zoom: f32, meta: &mut Metadata) {
+    fn apply_zoom(&self, new_zoom: f32, meta: &mut Metadata) {
         let diff = new_zoom - meta.zoom;
         meta.zoom = new_zoom;
 
@@ -644,8 +2094,1047 @@ where
 
     #[cfg(feature = "events")]
     fn publish_event(&self, event: Event) {
+        self.last_events.borrow_mut().push(event.clone());
+
         if let Some(sender) = self.events_publisher {
             sender.send(event).unwrap();
         }
     }
 }
+
+/// Whether the zoom input comes from a pinch gesture rather than from the mouse wheel.
+fn is_pinch(input: &InputState) -> bool {
+    input.multi_touch().is_some()
+        || input
+            .events
+            .iter()
+            .any(|e| matches!(e, egui::Event::Zoom(_)))
+}
+
+/// Converts the zoom factor of the input into the zoom step applied to the view.
+///
+/// Pinch gestures are followed proportionally, while any wheel input zooms by the fixed
+/// `speed` step regardless of how far the wheel was scrolled.
+fn zoom_step(delta: f32, pinch: bool, speed: f32, invert: bool) -> f32 {
+    let delta = if invert { delta.recip() } else { delta };
+    if pinch {
+        delta - 1.
+    } else {
+        speed * (delta - 1.).signum()
+    }
+}
+
+/// Clamps the pan along one axis so that the graph spanning from `min` to `max` in zoomed
+/// canvas units overlaps the `widget` range by `margin` of its length, or by the whole graph
+/// if it is shorter than that.
+fn clamp_pan_axis(pan: f32, min: f32, max: f32, widget: Rangef, margin: f32) -> f32 {
+    let overlap = (margin * widget.span()).min(max - min);
+    let lo = widget.min + overlap - max;
+    let hi = widget.max - overlap - min;
+    // lo <= hi as long as the margin is at most 1
+    pan.clamp(lo, hi.max(lo))
+}
+
+/// Rounds the position to the closest multiple of the `cell` size.
+fn snap_to_grid(pos: Pos2, cell: f32) -> Pos2 {
+    ((pos.to_vec2() / cell).round() * cell).to_pos2()
+}
+
+/// Unit direction in which the content moves for the held arrow keys, so that pressing
+/// the left arrow reveals what is to the left of the view.
+fn arrow_keys_direction(input: &InputState) -> Vec2 {
+    let mut direction = Vec2::ZERO;
+    for (key, dir) in [
+        (Key::ArrowLeft, Vec2::RIGHT),
+        (Key::ArrowRight, Vec2::LEFT),
+        (Key::ArrowUp, Vec2::DOWN),
+        (Key::ArrowDown, Vec2::UP),
+    ] {
+        if input.key_down(key) {
+            direction += dir;
+        }
+    }
+
+    direction.normalized()
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+    use crate::settings::DrawOrder;
+
+    fn graph_with_selection() -> (Graph, [NodeIndex; 3]) {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+        let b = g.add_node_with_location((), Pos2::new(10., 0.));
+        let c = g.add_node_with_location((), Pos2::new(20., 0.));
+
+        g.node_mut(a).unwrap().set_selected(true);
+        g.node_mut(b).unwrap().set_selected(true);
+
+        (g, [a, b, c])
+    }
+
+    #[test]
+    fn test_group_drag_moves_each_selected_node_once() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let settings = SettingsInteraction::new()
+            .with_dragging_enabled(true)
+            .with_node_selection_multi_enabled(true);
+
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+        view.move_dragged(a, Vec2::new(5., 5.));
+
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(5., 5.));
+        assert_eq!(g.node(b).unwrap().location(), Pos2::new(15., 5.));
+        assert_eq!(g.node(c).unwrap().location(), Pos2::new(20., 0.));
+    }
+
+    #[test]
+    fn test_group_drag_falls_back_to_single_above_limit() {
+        let (mut g, [a, b, _]) = graph_with_selection();
+        let settings = SettingsInteraction::new()
+            .with_dragging_enabled(true)
+            .with_node_selection_multi_enabled(true)
+            .with_max_group_drag(Some(1));
+
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+        view.move_dragged(a, Vec2::new(5., 5.));
+
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(5., 5.));
+        assert_eq!(g.node(b).unwrap().location(), Pos2::new(10., 0.));
+    }
+
+    #[test]
+    fn test_drag_of_unselected_node_moves_only_it() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let settings = SettingsInteraction::new()
+            .with_dragging_enabled(true)
+            .with_node_selection_multi_enabled(true);
+
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+        view.move_dragged(c, Vec2::new(5., 5.));
+
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(0., 0.));
+        assert_eq!(g.node(b).unwrap().location(), Pos2::new(10., 0.));
+        assert_eq!(g.node(c).unwrap().location(), Pos2::new(25., 5.));
+    }
+
+    #[test]
+    fn test_toggle_node_selection() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let settings = SettingsInteraction::new().with_node_selection_enabled(true);
+
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+        view.toggle_node_selection(c);
+        assert_eq!(view.graph().selected_nodes(), [c]);
+
+        view.toggle_node_selection(a);
+        assert_eq!(view.graph().selected_nodes(), [a]);
+
+        view.toggle_node_selection(a);
+        assert!(view.graph().selected_nodes().is_empty());
+        assert!(!g.node(b).unwrap().selected());
+    }
+
+    #[test]
+    fn test_toggle_node_selection_multi() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let settings = SettingsInteraction::new()
+            .with_node_selection_enabled(true)
+            .with_node_selection_multi_enabled(true);
+
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+        view.toggle_node_selection(c);
+        view.toggle_node_selection(a);
+
+        assert_eq!(view.graph().selected_nodes(), [b, c]);
+    }
+
+    #[test]
+    fn test_rotate_layout() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let mut view = DefaultGraphView::new(&mut g);
+
+        // centroid of the nodes is (10, 0)
+        view.rotate_layout(std::f32::consts::FRAC_PI_2, None);
+        let loc = |idx| view.graph().node(idx).unwrap().location();
+        assert!(loc(a).distance(Pos2::new(10., -10.)) < 1e-4);
+        assert!(loc(b).distance(Pos2::new(10., 0.)) < 1e-4);
+        assert!(loc(c).distance(Pos2::new(10., 10.)) < 1e-4);
+    }
+
+    #[test]
+    fn test_rotate_layout_full_turn() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let mut view = DefaultGraphView::new(&mut g);
+
+        view.rotate_layout(std::f32::consts::TAU, Some(Vec2::new(-7., 3.)));
+
+        for (idx, expected) in [(a, 0.), (b, 10.), (c, 20.)] {
+            let loc = view.graph().node(idx).unwrap().location();
+            assert!(loc.distance(Pos2::new(expected, 0.)) < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_drag_does_not_drift() {
+        let (mut g, [a, b, _]) = graph_with_selection();
+        let settings = SettingsInteraction::new()
+            .with_dragging_enabled(true)
+            .with_node_selection_multi_enabled(true);
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_displays();
+        let mut meta = Metadata::default();
+        meta.zoom = 0.37;
+        meta.pan = Vec2::new(50., 50.);
+        view.sync_state(&mut meta);
+
+        // the node is grabbed off its center, so the grab offset has to be kept
+        let grab = meta.canvas_to_screen_pos(Pos2::new(0.5, -0.5));
+        let step = Vec2::new(1.3, -0.7);
+        let frames = 100;
+        let ctx = egui::Context::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(500.));
+        let mut frame = |events: Vec<egui::Event>| {
+            let input = egui::RawInput {
+                screen_rect: Some(rect),
+                events,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let resp = ui.allocate_rect(ui.max_rect(), Sense::click_and_drag());
+                    view.handle_node_drag(&resp, &mut meta);
+                });
+            });
+        };
+        let button = |pos, pressed| egui::Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: egui::Modifiers::NONE,
+        };
+
+        frame(vec![egui::Event::PointerMoved(grab)]);
+        frame(vec![button(grab, true)]);
+        let mut pointer = grab;
+        for _ in 0..frames {
+            pointer += step;
+            frame(vec![egui::Event::PointerMoved(pointer)]);
+        }
+        frame(vec![button(pointer, false)]);
+        frame(vec![]);
+
+        let offset = (pointer - grab) / meta.zoom;
+        let loc = |idx| view.graph().node(idx).unwrap().location();
+        assert!(loc(a).distance(Pos2::ZERO + offset) < 1e-3, "{:?}", loc(a));
+        assert!(loc(b).distance(Pos2::new(10., 0.) + offset) < 1e-3);
+        assert!(view.graph().dragged_node().is_none());
+
+        #[cfg(feature = "events")]
+        {
+            let events = view.take_last_events();
+            let count = |f: fn(&Event) -> bool| events.iter().filter(|e| f(e)).count();
+            assert_eq!(count(|e| matches!(e, Event::NodeDragStart(_))), 1);
+            assert_eq!(count(|e| matches!(e, Event::NodeDragEnd(_))), 1);
+            // every frame the pointer moved moves both nodes of the group once
+            let moves = count(|e| matches!(e, Event::NodeMove(_)));
+            assert!(moves <= 2 * frames, "{moves} moves");
+            for idx in [a, b] {
+                let diff = events
+                    .iter()
+                    .filter_map(|e| match e {
+                        Event::NodeMove(m) if m.id == idx.index() => Some(Vec2::from(m.diff)),
+                        _ => None,
+                    })
+                    .fold(Vec2::ZERO, |sum, diff| sum + diff);
+                assert!((diff - offset).length() < 1e-3, "{diff:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_pinned_node() {
+        let (mut g, [a, b, _]) = graph_with_selection();
+        g.node_mut(a).unwrap().set_pinned(true);
+        let settings = SettingsInteraction::new()
+            .with_dragging_enabled(true)
+            .with_node_selection_multi_enabled(true);
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+
+        view.move_node(a, Vec2::new(5., 5.));
+        assert_eq!(view.graph().node(a).unwrap().location(), Pos2::new(0., 0.));
+
+        // the rest of the selection still follows a drag
+        view.move_dragged(a, Vec2::new(5., 5.));
+        assert_eq!(view.graph().node(a).unwrap().location(), Pos2::new(0., 0.));
+        assert_eq!(view.graph().node(b).unwrap().location(), Pos2::new(15., 5.));
+    }
+
+    #[test]
+    fn test_set_hovered_edge() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let ab = g.add_edge(a, b, ());
+        let bc = g.add_edge(b, c, ());
+        let mut view = DefaultGraphView::new(&mut g);
+        view.sync_state(&mut Metadata::default());
+
+        view.set_hovered_edge(Some(ab));
+        view.set_hovered_edge(Some(bc));
+        assert!(!view.graph().edge(ab).unwrap().hovered());
+        assert!(view.graph().edge(bc).unwrap().hovered());
+        assert_eq!(view.graph().hovered_edge(), Some(bc));
+
+        // hover is restored from the edges on the next frame
+        view.sync_state(&mut Metadata::default());
+        assert_eq!(view.graph().hovered_edge(), Some(bc));
+
+        view.set_hovered_edge(None);
+        assert!(!view.graph().edge(bc).unwrap().hovered());
+        assert_eq!(view.graph().hovered_edge(), None);
+    }
+
+    #[test]
+    fn test_set_hovered() {
+        let (mut g, [a, b, _]) = graph_with_selection();
+        let mut view = DefaultGraphView::new(&mut g);
+        view.sync_state(&mut Metadata::default());
+
+        view.set_hovered(Some(a));
+        view.set_hovered(Some(b));
+        assert!(!view.graph().node(a).unwrap().hovered());
+        assert!(view.graph().node(b).unwrap().hovered());
+        assert_eq!(view.graph().hovered_node(), Some(b));
+
+        // pointer left the widget
+        view.set_hovered(None);
+        assert!(!view.graph().node(b).unwrap().hovered());
+        assert_eq!(view.graph().hovered_node(), None);
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+        let b = g.add_node_with_location((), Pos2::new(20., 0.));
+        let interactions = SettingsInteraction::new()
+            .with_dragging_enabled(true)
+            .with_node_selection_enabled(true);
+        let log = [
+            Interaction::Click { pos: [20., 0.] },
+            Interaction::NodeDrag {
+                from: [20., 0.],
+                delta: [10., 10.],
+            },
+            Interaction::Zoom {
+                delta: 1.,
+                center: Some([0., 0.]),
+            },
+            Interaction::Pan { delta: [5., 0.] },
+            // hits node `a` at the origin after zoom and pan
+            Interaction::Click { pos: [5., 0.] },
+        ];
+
+        let mut meta = Metadata::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&interactions);
+        view.replay(rect, &mut meta, &log);
+
+        assert_eq!(meta.zoom, 2.);
+        assert_eq!(meta.pan, Vec2::new(5., 0.));
+        assert_eq!(g.node(b).unwrap().location(), Pos2::new(30., 10.));
+        assert!(!g.node(b).unwrap().selected());
+        assert_eq!(g.selected_nodes(), [a]);
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn test_replay_coalesces_drag_frames() {
+        use crate::events::{PayloadNodeDragEnd, PayloadNodeDragStart};
+
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+        let interactions = SettingsInteraction::new().with_dragging_enabled(true);
+        let drag = |from: [f32; 2], dx: f32| Interaction::NodeDrag {
+            from,
+            delta: [dx, 0.],
+        };
+        let count =
+            |events: &[Event], kind: fn(&Event) -> bool| events.iter().filter(|e| kind(e)).count();
+        let is_start = |e: &Event| matches!(e, Event::NodeDragStart(_));
+        let is_end = |e: &Event| matches!(e, Event::NodeDragEnd(_));
+        let is_move = |e: &Event| matches!(e, Event::NodeMove(_));
+
+        let mut meta = Metadata::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&interactions);
+
+        // frames of a single drag, each starting where the previous one ended
+        let log = [
+            drag([0., 0.], 10.),
+            drag([10., 0.], 10.),
+            drag([20., 0.], 10.),
+        ];
+        view.replay(rect, &mut meta, &log);
+        let events = view.take_last_events();
+        assert_eq!(
+            events.first(),
+            Some(&Event::NodeDragStart(PayloadNodeDragStart {
+                id: a.index()
+            }))
+        );
+        assert_eq!(
+            events.last(),
+            Some(&Event::NodeDragEnd(PayloadNodeDragEnd { id: a.index() }))
+        );
+        assert_eq!(count(&events, is_start), 1);
+        assert_eq!(count(&events, is_move), 3);
+        assert_eq!(count(&events, is_end), 1);
+
+        // the second frame does not continue the first one, so it is another drag
+        let log = [drag([30., 0.], 2.), drag([30., 0.], 2.)];
+        view.replay(rect, &mut meta, &log);
+        let events = view.take_last_events();
+        assert_eq!(count(&events, is_start), 2);
+        assert_eq!(count(&events, is_end), 2);
+
+        assert!(!g.node(a).unwrap().dragged());
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(34., 0.));
+    }
+
+    #[test]
+    fn test_double_click_does_not_toggle_selection() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+        let interactions = SettingsInteraction::new()
+            .with_node_selection_enabled(true)
+            .with_node_double_clicking_enabled(true);
+        let log = [
+            Interaction::Click { pos: [0., 0.] },
+            Interaction::DoubleClick { pos: [0., 0.] },
+        ];
+
+        let mut meta = Metadata::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&interactions);
+        view.replay(rect, &mut meta, &log);
+
+        assert!(g.node(a).unwrap().selected());
+    }
+
+    #[test]
+    fn test_nodes_index_matches_linear_search() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        for i in 0..100 {
+            // overlapping nodes so that the draw order decides the hit
+            g.add_node_with_location((), Pos2::new((i % 10) as f32 * 4., (i / 10) as f32 * 4.));
+        }
+        g.node_mut(NodeIndex::new(42)).unwrap().set_selected(true);
+        g.node_mut(NodeIndex::new(42)).unwrap().set_z(-1);
+        g.node_mut(NodeIndex::new(31)).unwrap().set_z(1);
+        DefaultGraphView::new(&mut g).sync_displays();
+
+        let meta = Metadata::default();
+        let positions = (0..50)
+            .flat_map(|x| (0..50).map(move |y| Pos2::new(x as f32 - 5., y as f32 - 5.)))
+            .collect::<Vec<_>>();
+        let orders = [
+            DrawOrder::Insertion,
+            DrawOrder::ByZ,
+            DrawOrder::SelectedLast,
+        ];
+        let linear = orders
+            .iter()
+            .flat_map(|o| {
+                positions
+                    .iter()
+                    .map(|p| g.node_by_screen_pos_ordered(&meta, *p, *o))
+            })
+            .collect::<Vec<_>>();
+
+        g.update_nodes_index();
+        let indexed = orders
+            .iter()
+            .flat_map(|o| {
+                positions
+                    .iter()
+                    .map(|p| g.node_by_screen_pos_ordered(&meta, *p, *o))
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(indexed, linear);
+    }
+
+    #[test]
+    fn test_hit_test_reuses_drawn_order() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::ZERO);
+        let b = g.add_node_with_location((), Pos2::ZERO);
+        DefaultGraphView::new(&mut g).sync_displays();
+        let meta = Metadata::default();
+
+        g.update_nodes_order(DrawOrder::SelectedLast);
+        g.node_mut(a).unwrap().set_selected(true);
+        // hits follow the order the nodes were drawn in until the next update
+        assert_eq!(
+            g.node_by_screen_pos_ordered(&meta, Pos2::ZERO, DrawOrder::SelectedLast),
+            Some(b)
+        );
+
+        g.update_nodes_order(DrawOrder::SelectedLast);
+        assert_eq!(
+            g.node_by_screen_pos_ordered(&meta, Pos2::ZERO, DrawOrder::SelectedLast),
+            Some(a)
+        );
+    }
+
+    #[test]
+    fn test_added_node_is_found_before_index_update() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        g.add_node_with_location((), Pos2::ZERO);
+        DefaultGraphView::new(&mut g).sync_displays();
+        g.update_nodes_order(DrawOrder::SelectedLast);
+        g.update_nodes_index();
+
+        let b = g.add_node_with_location((), Pos2::new(100., 0.));
+        DefaultGraphView::new(&mut g).sync_displays();
+        let meta = Metadata::default();
+        assert_eq!(
+            g.node_by_screen_pos_ordered(&meta, Pos2::new(100., 0.), DrawOrder::SelectedLast),
+            Some(b)
+        );
+
+        g.remove_node(b);
+        assert_eq!(
+            g.node_by_screen_pos_ordered(&meta, Pos2::new(100., 0.), DrawOrder::SelectedLast),
+            None
+        );
+    }
+
+    #[test]
+    fn test_nodes_draw_order_respects_z() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let idxs = (0..4)
+            .map(|_| g.add_node_with_location((), Pos2::ZERO))
+            .collect::<Vec<_>>();
+        g.node_mut(idxs[0]).unwrap().set_z(2);
+        g.node_mut(idxs[2]).unwrap().set_z(-1);
+        g.node_mut(idxs[3]).unwrap().set_z(2);
+
+        assert_eq!(
+            g.nodes_draw_order(DrawOrder::ByZ),
+            vec![idxs[2], idxs[1], idxs[0], idxs[3]]
+        );
+        assert_eq!(g.nodes_draw_order(DrawOrder::Insertion), idxs);
+
+        // interacted nodes are on top regardless of z
+        g.node_mut(idxs[2]).unwrap().set_selected(true);
+        assert_eq!(
+            g.nodes_draw_order(DrawOrder::SelectedLast),
+            vec![idxs[1], idxs[0], idxs[3], idxs[2]]
+        );
+        assert_eq!(
+            g.nodes_draw_order(DrawOrder::ByZ),
+            vec![idxs[2], idxs[1], idxs[0], idxs[3]]
+        );
+        assert_eq!(g.nodes_draw_order(DrawOrder::Insertion), idxs);
+    }
+
+    #[test]
+    fn test_selection_accessors() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let ab = g.add_edge(a, b, ());
+        let bc = g.add_edge(b, c, ());
+        g.edge_mut(bc).unwrap().set_selected(true);
+        g.remove_node(c);
+
+        let view = DefaultGraphView::new(&mut g);
+        assert_eq!(view.selected_nodes(), [a, b]);
+        assert!(view.selected_edges().is_empty());
+        assert!(view.is_node_selected(a));
+        assert!(!view.is_node_selected(c));
+        assert!(!view.is_edge_selected(ab));
+        assert!(!view.is_edge_selected(bc));
+    }
+
+    #[test]
+    fn test_arrow_keys_direction() {
+        let press = |keys: &[Key]| {
+            let ctx = egui::Context::default();
+            let events = keys
+                .iter()
+                .map(|key| egui::Event::Key {
+                    key: *key,
+                    physical_key: None,
+                    pressed: true,
+                    repeat: false,
+                    modifiers: egui::Modifiers::NONE,
+                })
+                .collect();
+            let mut direction = None;
+            let _ = ctx.run(
+                egui::RawInput {
+                    events,
+                    ..Default::default()
+                },
+                |ctx| direction = Some(ctx.input(arrow_keys_direction)),
+            );
+            direction.unwrap()
+        };
+
+        assert_eq!(press(&[]), Vec2::ZERO);
+        assert_eq!(press(&[Key::ArrowLeft]), Vec2::RIGHT);
+        assert_eq!(press(&[Key::ArrowUp]), Vec2::DOWN);
+        assert_eq!(press(&[Key::ArrowLeft, Key::ArrowRight]), Vec2::ZERO);
+        let diagonal = press(&[Key::ArrowRight, Key::ArrowDown]);
+        assert!((diagonal - Vec2::new(-1., -1.).normalized()).length() < 1e-6);
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn test_request_deletion() {
+        use crate::events::{PayloadEdgeDelete, PayloadNodeDelete};
+
+        let (mut g, [a, b, c]) = graph_with_selection();
+        g.add_edge(a, c, ());
+        let bc = g.add_edge(b, c, ());
+        g.edge_mut(bc).unwrap().set_selected(true);
+
+        let mut view = DefaultGraphView::new(&mut g);
+        view.request_deletion();
+        assert_eq!(
+            view.take_last_events(),
+            [
+                Event::EdgeDelete(PayloadEdgeDelete {
+                    id: bc.index(),
+                    source: b.index(),
+                    target: c.index(),
+                }),
+                Event::NodeDelete(PayloadNodeDelete { id: a.index() }),
+                Event::NodeDelete(PayloadNodeDelete { id: b.index() }),
+            ]
+        );
+        // removal is up to the caller
+        assert_eq!(g.node_count(), 3);
+        assert_eq!(g.edge_count(), 2);
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn test_finish_edge_draft() {
+        use crate::events::PayloadEdgeCreate;
+
+        let (mut g, [a, _, c]) = graph_with_selection();
+        let mut meta = Metadata::default();
+        let mut view = DefaultGraphView::new(&mut g);
+        view.sync_displays();
+
+        for (end, target) in [(Pos2::new(20., 0.), Some(c)), (Pos2::new(0., 50.), None)] {
+            meta.start_edge_draft(a.index(), Pos2::new(0., 0.));
+            meta.update_edge_draft(end);
+            view.finish_edge_draft(&mut meta);
+            assert!(meta.edge_draft().is_none());
+
+            let expected = target.map(|target| {
+                Event::EdgeCreate(PayloadEdgeCreate {
+                    source: a.index(),
+                    target: target.index(),
+                })
+            });
+            assert_eq!(view.take_last_events(), Vec::from_iter(expected));
+        }
+
+        // dropping on the source node
+        meta.start_edge_draft(a.index(), Pos2::new(0., 0.));
+        view.finish_edge_draft(&mut meta);
+        assert!(view.take_last_events().is_empty());
+    }
+
+    #[test]
+    fn test_snap_to_grid() {
+        assert_eq!(snap_to_grid(Pos2::new(12., -7.), 10.), Pos2::new(10., -10.));
+        assert_eq!(snap_to_grid(Pos2::new(14.9, 25.), 10.), Pos2::new(10., 30.));
+
+        let (mut g, [a, b, _]) = graph_with_selection();
+        g.node_mut(b).unwrap().set_location(Pos2::new(40., 0.));
+        let settings = SettingsInteraction::new()
+            .with_dragging_enabled(true)
+            .with_node_selection_multi_enabled(true)
+            .with_snap_to_grid(Some(20.));
+
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+        view.move_dragged(a, Vec2::new(3., 3.));
+        assert_eq!(view.g.node(a).unwrap().location(), Pos2::new(0., 0.));
+        view.move_dragged(a, Vec2::new(12., 3.));
+
+        // the selection is moved by the same snapped delta
+        assert_eq!(g.node(a).unwrap().location(), Pos2::new(20., 0.));
+        assert_eq!(g.node(b).unwrap().location(), Pos2::new(60., 0.));
+    }
+
+    #[test]
+    fn test_frame_bounds_exactly() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let navigation = SettingsNavigation::new().with_zoom_max(3.);
+        let view = DefaultGraphView::new(&mut g).with_navigations(&navigation);
+
+        let widget = Rect::from_min_size(Pos2::ZERO, Vec2::new(200., 100.));
+        let mut meta = Metadata::default();
+        meta.first_frame = false;
+        view.frame_bounds(
+            &widget,
+            Rect::from_min_max(Pos2::new(10., 10.), Pos2::new(30., 20.)),
+            0.,
+            None,
+            &mut meta,
+        );
+        // zoom of 10 is clamped
+        assert_eq!(meta.zoom, 3.);
+        assert_eq!(
+            meta.canvas_to_screen_pos(Pos2::new(20., 15.)),
+            widget.center()
+        );
+
+        view.frame_bounds(
+            &widget,
+            Rect::from_min_max(Pos2::new(0., 0.), Pos2::new(400., 100.)),
+            0.,
+            None,
+            &mut meta,
+        );
+        assert_eq!(meta.zoom, 0.5);
+        assert_eq!(
+            meta.canvas_to_screen_pos(Pos2::new(0., 0.)),
+            Pos2::new(0., 25.)
+        );
+    }
+
+    #[test]
+    fn test_zoom_step() {
+        // wheel zooms by the fixed step
+        assert_eq!(zoom_step(1.01, false, 0.1, false), 0.1);
+        assert_eq!(zoom_step(1.5, false, 0.1, false), 0.1);
+        assert_eq!(zoom_step(0.8, false, 0.1, false), -0.1);
+        assert_eq!(zoom_step(1.5, false, 0.1, true), -0.1);
+
+        // pinch follows the gesture
+        assert_eq!(zoom_step(1.5, true, 0.1, false), 0.5);
+        assert_eq!(zoom_step(0.5, true, 0.1, false), -0.5);
+        assert_eq!(zoom_step(0.5, true, 0.1, true), 1.);
+    }
+
+    #[test]
+    fn test_clamp_pan_axis() {
+        let widget = Rangef::new(0., 100.);
+
+        // a fifth of the widget stays covered by the graph
+        assert_eq!(clamp_pan_axis(-1000., 0., 50., widget, 0.2), -30.);
+        assert_eq!(clamp_pan_axis(1000., 0., 50., widget, 0.2), 80.);
+        assert_eq!(clamp_pan_axis(10., 0., 50., widget, 0.2), 10.);
+
+        // a graph smaller than the margin stays entirely inside
+        assert_eq!(clamp_pan_axis(-1000., 0., 10., widget, 0.2), 0.);
+        assert_eq!(clamp_pan_axis(1000., 0., 10., widget, 0.2), 90.);
+
+        // a graph larger than the widget may cover it entirely
+        assert_eq!(clamp_pan_axis(-150., 0., 400., widget, 1.), -150.);
+        assert_eq!(clamp_pan_axis(-1000., 0., 400., widget, 1.), -300.);
+    }
+
+    #[test]
+    fn test_zoom_is_clamped() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let navigation = SettingsNavigation::new()
+            .with_zoom_min(0.5)
+            .with_zoom_max(4.);
+        let view = DefaultGraphView::new(&mut g).with_navigations(&navigation);
+
+        let mut meta = Metadata::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        let center = Pos2::new(30., 70.);
+        let anchor = meta.screen_to_canvas_pos(center);
+
+        for _ in 0..100 {
+            view.zoom_by(&rect, 0.5, Some(center), &mut meta);
+            assert!(meta.zoom <= 4.);
+        }
+        assert_eq!(meta.zoom, 4.);
+        assert!(meta.canvas_to_screen_pos(anchor).distance(center) < 1e-3);
+
+        for _ in 0..100 {
+            view.zoom_by(&rect, -0.5, Some(center), &mut meta);
+            assert!(meta.zoom >= 0.5);
+        }
+        assert_eq!(meta.zoom, 0.5);
+        assert!(meta.canvas_to_screen_pos(anchor).distance(center) < 1e-3);
+    }
+
+    #[test]
+    fn test_fit_to_screen_empty_graph() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let view = DefaultGraphView::new(&mut g);
+
+        let mut meta = Metadata::default();
+        assert_eq!(meta.graph_bounds(), None);
+
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        view.fit_bounds(
+            &rect,
+            Rect::from_center_size(Pos2::ZERO, Vec2::ZERO),
+            &mut meta,
+        );
+        assert!(meta.zoom.is_finite());
+        assert!(meta.pan.x.is_finite() && meta.pan.y.is_finite());
+    }
+
+    #[test]
+    fn test_fit_postponed_until_graph_has_nodes() {
+        let ctx = Context::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let mut meta = Metadata::default();
+        let navigation = SettingsNavigation::new().with_fit_to_screen_enabled(false);
+
+        let mut view = DefaultGraphView::new(&mut g).with_navigations(&navigation);
+        view.sync_state(&mut meta);
+        assert!(!view.fit_to_screen(&ctx, &rect, &mut meta));
+        assert!(meta.fit_pending());
+        meta.first_frame = false;
+        drop(view);
+
+        g.add_node_with_location((), Pos2::new(10., 10.));
+        g.add_node_with_location((), Pos2::new(30., 10.));
+        let mut view = DefaultGraphView::new(&mut g).with_navigations(&navigation);
+        view.sync_state(&mut meta);
+        assert!(view.fit_to_screen(&ctx, &rect, &mut meta));
+        assert!(!meta.fit_pending());
+        assert_ne!(meta.zoom, 1.);
+
+        meta.zoom = 1.;
+        assert!(!view.fit_to_screen(&ctx, &rect, &mut meta));
+        assert_eq!(meta.zoom, 1.);
+    }
+
+    #[test]
+    fn test_fit_to_screen_single_node() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        g.add_node_with_location((), Pos2::new(10., 10.));
+        let mut view = DefaultGraphView::new(&mut g);
+
+        let mut meta = Metadata::default();
+        view.sync_state(&mut meta);
+        let bounds = meta.graph_bounds().unwrap();
+
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        view.fit_bounds(&rect, bounds, &mut meta);
+        assert!(meta.zoom.is_finite() && meta.zoom > 0.);
+        assert!(meta.pan.x.is_finite() && meta.pan.y.is_finite());
+
+        view.fit_bounds(
+            &rect,
+            Rect::from_center_size(Pos2::ZERO, Vec2::ZERO),
+            &mut meta,
+        );
+        assert!(meta.zoom.is_finite() && meta.zoom > 0.);
+        assert!(meta.pan.x.is_finite() && meta.pan.y.is_finite());
+    }
+
+    #[test]
+    fn test_fit_at_empty_space() {
+        let (mut g, [a, _, _]) = graph_with_selection();
+        let mut view = DefaultGraphView::new(&mut g);
+        let mut meta = Metadata::default();
+        view.sync_state(&mut meta);
+
+        let ctx = Context::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(100.));
+        let node_pos = meta.canvas_to_screen_pos(view.graph().node(a).unwrap().location());
+        assert!(!view.fit_at(&ctx, &rect, node_pos, &mut meta));
+        assert_eq!(meta.zoom, 1.);
+
+        assert!(view.fit_at(&ctx, &rect, Pos2::new(50., 50.), &mut meta));
+        assert_ne!(meta.zoom, 1.);
+    }
+
+    #[test]
+    fn test_edge_select_tolerance() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+        let b = g.add_node_with_location((), Pos2::new(100., 0.));
+        let e = g.add_edge(a, b, ());
+        let settings = SettingsInteraction::new().with_edge_select_tolerance(4.);
+        let view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+
+        // the default edge is 2 units wide, so its outline is 1 unit off its center line
+        for zoom in [0.5, 1., 4.] {
+            let mut meta = Metadata::default();
+            meta.zoom = zoom;
+            let off_edge = |d: f32| meta.canvas_to_screen_pos(Pos2::new(50., 1.)) + Vec2::Y * d;
+            assert_eq!(view.edge_by_screen_pos(&meta, off_edge(3.9)), Some(e));
+            assert_eq!(view.edge_by_screen_pos(&meta, off_edge(4.1)), None);
+        }
+    }
+
+    #[test]
+    fn test_visible_nodes() {
+        let (mut g, [a, b, _]) = graph_with_selection();
+        let view = DefaultGraphView::new(&mut g);
+        let mut meta = Metadata::default();
+        meta.zoom = 2.;
+        meta.pan = Vec2::new(10., 0.);
+
+        let rect = Rect::from_min_max(Pos2::new(0., -10.), Pos2::new(32., 10.));
+        let visible = view.visible_nodes(&meta, rect).collect::<Vec<_>>();
+        assert_eq!(
+            visible,
+            vec![(a, Pos2::new(10., 0.)), (b, Pos2::new(30., 0.))]
+        );
+
+        let rect = Rect::from_min_max(Pos2::new(100., -10.), Pos2::new(200., 10.));
+        assert_eq!(view.visible_nodes(&meta, rect).next(), None);
+    }
+
+    #[test]
+    fn test_graph_bounds() {
+        let (mut g, [a, _, _]) = graph_with_selection();
+        let view = DefaultGraphView::new(&mut g);
+        assert_eq!(
+            view.graph_bounds(),
+            Some(Rect::from_min_max(Pos2::new(-5., -5.), Pos2::new(25., 5.)))
+        );
+
+        g.node_mut(a).unwrap().set_location(Pos2::new(-50., 0.));
+        let view = DefaultGraphView::new(&mut g);
+        assert_eq!(view.graph_bounds().unwrap().min, Pos2::new(-55., -5.));
+
+        let mut empty: Graph = Graph::new(StableGraph::default());
+        assert_eq!(DefaultGraphView::new(&mut empty).graph_bounds(), None);
+    }
+
+    #[test]
+    fn test_center_on_node() {
+        let (mut g, [_, b, _]) = graph_with_selection();
+        let view = DefaultGraphView::new(&mut g);
+
+        let mut meta = Metadata::default();
+        meta.zoom = 2.5;
+        meta.pan = Vec2::new(-30., 12.);
+        meta.rect = Rect::from_min_size(Pos2::new(20., 40.), Vec2::new(200., 100.));
+        view.center_meta_on_node(b, &mut meta);
+
+        assert_eq!(meta.zoom, 2.5);
+        let screen = meta.canvas_to_screen_pos(view.graph().node(b).unwrap().location());
+        assert!(screen.distance(meta.rect.center()) < 1e-4);
+
+        // unknown node is ignored
+        let pan = meta.pan;
+        view.center_meta_on_node(NodeIndex::new(42), &mut meta);
+        assert_eq!(meta.pan, pan);
+    }
+
+    #[test]
+    fn test_selection_bounds() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let mut view = DefaultGraphView::new(&mut g);
+        let mut meta = Metadata::default();
+        view.sync_state(&mut meta);
+
+        let bounds = view.selection_bounds(&meta, false).unwrap();
+        let graph_bounds = meta.graph_bounds().unwrap();
+        assert!(bounds.width() < graph_bounds.width());
+
+        view.g.node_mut(a).unwrap().set_selected(false);
+        view.g.node_mut(b).unwrap().set_selected(false);
+        view.sync_state(&mut meta);
+        assert_eq!(view.selection_bounds(&meta, false), None);
+        assert_eq!(view.selection_bounds(&meta, true), Some(graph_bounds));
+
+        view.g.node_mut(c).unwrap().set_selected(true);
+        view.sync_state(&mut meta);
+        let bounds = nodes_bounds(view.graph().node(c));
+        assert_eq!(view.selection_bounds(&meta, false), bounds);
+    }
+
+    #[test]
+    fn test_clear_selection_respects_settings() {
+        let (mut g, [a, b, _]) = graph_with_selection();
+        let mut view = DefaultGraphView::new(&mut g);
+        view.sync_state(&mut Metadata::default());
+        view.clear_selection();
+        assert_eq!(view.graph().selected_nodes(), [a, b]);
+
+        let settings = SettingsInteraction::new().with_node_selection_enabled(true);
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+        view.clear_selection();
+        assert!(!view.graph().node(a).unwrap().selected());
+        assert!(!view.graph().node(b).unwrap().selected());
+    }
+
+    #[test]
+    fn test_select_all_and_invert() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        let ab = g.add_edge(a, b, ());
+        let settings = SettingsInteraction::new().with_node_selection_multi_enabled(true);
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.sync_state(&mut Metadata::default());
+
+        view.select_all();
+        assert_eq!(view.graph().selected_nodes(), [a, b, c]);
+        // edges are selected only with their multiselection
+        assert!(view.graph().selected_edges().is_empty());
+
+        view.g.node_mut(c).unwrap().set_selected(false);
+        view.invert_selection();
+        assert_eq!(view.graph().selected_nodes(), [c]);
+
+        let settings = settings.with_edge_selection_multi_enabled(true);
+        let mut view = DefaultGraphView::new(&mut g).with_interactions(&settings);
+        view.select_all();
+        assert_eq!(view.graph().selected_edges(), [ab]);
+    }
+
+    #[test]
+    fn test_select_in_rect() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        g.node_mut(a).unwrap().set_selected(false);
+        let mut view = DefaultGraphView::new(&mut g);
+
+        view.select_in_rect(Rect::from_min_max(Pos2::new(5., -1.), Pos2::new(25., 1.)));
+
+        assert!(!view.graph().node(a).unwrap().selected());
+        assert_eq!(view.graph().selected_nodes(), [b, c]);
+    }
+
+    #[test]
+    fn test_removed_nodes_are_dropped_from_state() {
+        let (mut g, [a, b, c]) = graph_with_selection();
+        g.node_mut(a).unwrap().set_dragged(true);
+        let mut meta = Metadata::default();
+        DefaultGraphView::new(&mut g).sync_state(&mut meta);
+        assert_eq!(g.dragged_node(), Some(a));
+
+        g.remove_node(a);
+        g.remove_node(b);
+
+        let mut view = DefaultGraphView::new(&mut g);
+        view.sync_state(&mut meta);
+        view.deselect_all();
+        view.move_dragged(a, Vec2::new(5., 5.));
+        view.set_drag_end(a);
+
+        assert!(g.selected_nodes().is_empty());
+        assert_eq!(g.dragged_node(), None);
+        assert_eq!(g.node(c).unwrap().location(), Pos2::new(20., 0.));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{DisplayEdge, DisplayNode, Edge, Graph, Node};
-use egui::Vec2;
+use egui::{Pos2, Vec2};
 use petgraph::{
     graph::IndexType,
     stable_graph::{EdgeIndex, NodeIndex, StableGraph},
@@ -7,7 +7,15 @@ use petgraph::{
     EdgeType,
 };
 use rand::Rng;
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, VecDeque},
+    f32::consts::TAU,
+};
+
+const DECLUTTER_MAX_ITERATIONS: usize = 100;
+const DECLUTTER_TOLERANCE: f32 = 1e-3;
+/// Angle between consecutive push directions of nodes sharing the same position.
+const GOLDEN_ANGLE: f32 = 2.399_963;
 
 /// Helper function which adds user's node to the [`super::Graph`] instance.
 ///
@@ -251,6 +259,235 @@ pub fn node_size<N: Clone, E: Clone, Ty: EdgeType, Ix: IndexType, D: DisplayNode
     ((connector_right.to_vec2() - connector_left.to_vec2()) / 2.).length()
 }
 
+/// Arranges nodes in concentric rings around the `center` node.
+///
+/// The `center` node keeps its location. Other nodes are placed on rings by their breadth-first
+/// distance from `center` ignoring edge directions, with `ring_gap` between consecutive rings.
+/// Nodes of a ring are spread evenly by angle. Nodes unreachable from `center` are placed on one
+/// extra ring outside of the outermost one.
+///
+/// Does nothing if `center` is not in the graph.
+pub fn layout_radial<N, E, Ty, Ix, Dn, De>(
+    g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+    center: NodeIndex<Ix>,
+    ring_gap: f32,
+) where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let Some(origin) = g.node(center).map(Node::location) else {
+        return;
+    };
+
+    let mut depths = HashMap::from([(center, 0)]);
+    let mut rings: Vec<Vec<NodeIndex<Ix>>> = vec![vec![center]];
+    let mut queue = VecDeque::from([center]);
+    while let Some(idx) = queue.pop_front() {
+        let depth = depths[&idx] + 1;
+        for neighbor in g.g.neighbors_undirected(idx) {
+            if depths.contains_key(&neighbor) {
+                continue;
+            }
+            depths.insert(neighbor, depth);
+            if rings.len() <= depth {
+                rings.push(vec![]);
+            }
+            rings[depth].push(neighbor);
+            queue.push_back(neighbor);
+        }
+    }
+
+    let unreachable =
+        g.g.node_indices()
+            .filter(|idx| !depths.contains_key(idx))
+            .collect::<Vec<_>>();
+    if !unreachable.is_empty() {
+        rings.push(unreachable);
+    }
+
+    for (depth, ring) in rings.iter().enumerate().skip(1) {
+        let radius = depth as f32 * ring_gap;
+        let step = TAU / ring.len() as f32;
+        for (i, idx) in ring.iter().enumerate() {
+            let angle = i as f32 * step;
+            let loc = origin + Vec2::angled(angle) * radius;
+            g.g[*idx].set_location(loc);
+        }
+    }
+}
+
+/// Places nodes evenly on a circle of the given `radius` around the origin in the order of
+/// their indices. The first node is placed on the positive x axis.
+pub fn layout_circular<N, E, Ty, Ix, Dn, De>(g: &mut Graph<N, E, Ty, Ix, Dn, De>, radius: f32)
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let step = TAU / g.node_count() as f32;
+    for (i, node) in g.g.node_weights_mut().enumerate() {
+        node.set_location((Vec2::angled(i as f32 * step) * radius).to_pos2());
+    }
+}
+
+/// Arranges nodes in rows of `cols` nodes with `spacing` between neighbors in the order of
+/// their indices, starting with the first node at the origin.
+///
+/// At least one column is used.
+pub fn layout_grid<N, E, Ty, Ix, Dn, De>(
+    g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+    cols: usize,
+    spacing: f32,
+) where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let cols = cols.max(1);
+    for (i, node) in g.g.node_weights_mut().enumerate() {
+        let (row, col) = (i / cols, i % cols);
+        node.set_location(Pos2::new(col as f32 * spacing, row as f32 * spacing));
+    }
+}
+
+/// Pushes apart overlapping nodes so that every pair of nodes is separated by at least `min_gap`
+/// between their boundaries.
+///
+/// Every iteration moves both nodes of each overlapping pair away from each other by half of
+/// the overlap. Nodes sharing the exact same position are pushed apart in distinct directions.
+/// Iterations stop when no overlaps remain or after a fixed number of iterations.
+///
+/// Returns `true` if all overlaps were resolved.
+pub fn declutter_positions<N, E, Ty, Ix, Dn, De>(
+    g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+    min_gap: f32,
+) -> bool
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let (idxs, radii): (Vec<_>, Vec<_>) = g
+        .nodes_iter()
+        .map(|(idx, n)| (idx, node_size(n, Vec2::X)))
+        .unzip();
+    let mut positions = idxs
+        .iter()
+        .map(|idx| g.g[*idx].location())
+        .collect::<Vec<_>>();
+
+    let mut resolved = false;
+    for _ in 0..DECLUTTER_MAX_ITERATIONS {
+        let mut moved = false;
+        for i in 0..positions.len() {
+            for j in i + 1..positions.len() {
+                let delta = positions[j] - positions[i];
+                let dist = delta.length();
+                let min_dist = radii[i] + radii[j] + min_gap;
+                if dist >= min_dist - DECLUTTER_TOLERANCE {
+                    continue;
+                }
+
+                let dir = if dist > f32::EPSILON {
+                    delta / dist
+                } else {
+                    Vec2::angled((i * positions.len() + j) as f32 * GOLDEN_ANGLE)
+                };
+                let push = dir * (min_dist - dist) / 2.;
+                positions[i] -= push;
+                positions[j] += push;
+                moved = true;
+            }
+        }
+
+        if !moved {
+            resolved = true;
+            break;
+        }
+    }
+
+    for (idx, pos) in idxs.into_iter().zip(positions) {
+        if g.g[idx].location() != pos {
+            g.g[idx].set_location(pos);
+        }
+    }
+
+    resolved
+}
+
+/// Computes degree centrality of every node normalized to the `0.0..=1.0` range,
+/// where `1.0` is assigned to the nodes with the highest degree.
+///
+/// The result can be used directly as node glow intensity, see [`Node::set_glow`].
+pub fn degree_centrality<N, E, Ty, Ix, Dn, De>(
+    g: &Graph<N, E, Ty, Ix, Dn, De>,
+) -> HashMap<NodeIndex<Ix>, f32>
+where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let degrees = g
+        .nodes_iter()
+        .map(|(idx, _)| (idx, g.incident_edges(idx).count()))
+        .collect::<HashMap<_, _>>();
+    let max = degrees.values().copied().max().unwrap_or_default().max(1);
+
+    degrees
+        .into_iter()
+        .map(|(idx, degree)| (idx, degree as f32 / max as f32))
+        .collect()
+}
+
+/// Sets the radius of every node by mapping `f` of its payload linearly into `min..=max`,
+/// so that the node with the smallest value gets `min` and the node with the largest one
+/// gets `max`. If all values are equal, all nodes get `min`.
+///
+/// Radii are stored in the nodes, see [`Node::set_radius`], so it is enough to call this
+/// once after the payloads change.
+pub fn size_nodes_by<N, E, Ty, Ix, Dn, De>(
+    g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+    f: impl Fn(&N) -> f32,
+    min: f32,
+    max: f32,
+) where
+    N: Clone,
+    E: Clone,
+    Ty: EdgeType,
+    Ix: IndexType,
+    Dn: DisplayNode<N, E, Ty, Ix>,
+    De: DisplayEdge<N, E, Ty, Ix, Dn>,
+{
+    let values =
+        g.g.node_weights()
+            .map(|n| f(n.payload()))
+            .collect::<Vec<_>>();
+    let lo = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let hi = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let range = hi - lo;
+
+    for (node, value) in g.g.node_weights_mut().zip(values) {
+        let t = if range > 0. { (value - lo) / range } else { 0. };
+        node.set_radius(min + (max - min) * t);
+    }
+}
+
 pub fn random_graph(num_nodes: usize, num_edges: usize) -> Graph {
     let mut rng = rand::thread_rng();
     let mut graph = StableGraph::new();
@@ -303,6 +540,138 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_layout_circular() {
+        let mut user_g: StableGraph<_, _, Directed> = StableGraph::new();
+        let nodes = (0..4).map(|_| user_g.add_node(())).collect::<Vec<_>>();
+        let mut g = to_graph::<_, (), _, _, DefaultNodeShape, DefaultEdgeShape>(&user_g);
+
+        layout_circular(&mut g, 10.);
+
+        let loc = |i: usize| g.node(nodes[i]).unwrap().location();
+        assert!(loc(0).distance(Pos2::new(10., 0.)) < 1e-4);
+        assert!(loc(1).distance(Pos2::new(0., 10.)) < 1e-4);
+        assert!(loc(2).distance(Pos2::new(-10., 0.)) < 1e-4);
+        assert!(loc(3).distance(Pos2::new(0., -10.)) < 1e-4);
+    }
+
+    #[test]
+    fn test_layout_grid() {
+        let mut user_g: StableGraph<_, _, Directed> = StableGraph::new();
+        let nodes = (0..5).map(|_| user_g.add_node(())).collect::<Vec<_>>();
+        let mut g = to_graph::<_, (), _, _, DefaultNodeShape, DefaultEdgeShape>(&user_g);
+
+        layout_grid(&mut g, 2, 30.);
+
+        let loc = |i: usize| g.node(nodes[i]).unwrap().location();
+        assert_eq!(loc(0), Pos2::new(0., 0.));
+        assert_eq!(loc(1), Pos2::new(30., 0.));
+        assert_eq!(loc(2), Pos2::new(0., 30.));
+        assert_eq!(loc(4), Pos2::new(0., 60.));
+    }
+
+    #[test]
+    fn test_layout_radial() {
+        let mut user_g: StableGraph<_, _, Directed> = StableGraph::new();
+        let center = user_g.add_node(());
+        let n1 = user_g.add_node(());
+        let n2 = user_g.add_node(());
+        let n3 = user_g.add_node(());
+        let isolated = user_g.add_node(());
+        user_g.add_edge(center, n1, ());
+        user_g.add_edge(n2, center, ());
+        user_g.add_edge(n1, n3, ());
+
+        let mut g = to_graph::<_, _, _, _, DefaultNodeShape, DefaultEdgeShape>(&user_g);
+        g.node_mut(center)
+            .unwrap()
+            .set_location(Pos2::new(10., 20.));
+
+        layout_radial(&mut g, center, 50.);
+
+        let origin = Pos2::new(10., 20.);
+        let dist = |idx| g.node(idx).unwrap().location().distance(origin);
+        assert_eq!(g.node(center).unwrap().location(), origin);
+        assert!((dist(n1) - 50.).abs() < 1e-3);
+        assert!((dist(n2) - 50.).abs() < 1e-3);
+        assert!((dist(n3) - 100.).abs() < 1e-3);
+        assert!((dist(isolated) - 150.).abs() < 1e-3);
+        assert!(
+            g.node(n1)
+                .unwrap()
+                .location()
+                .distance(g.node(n2).unwrap().location())
+                > 1.
+        );
+    }
+
+    #[test]
+    fn test_declutter_positions() {
+        let mut user_g: StableGraph<(), (), Directed> = StableGraph::new();
+        let idxs = (0..4).map(|_| user_g.add_node(())).collect::<Vec<_>>();
+
+        let mut g = to_graph::<_, _, _, _, DefaultNodeShape, DefaultEdgeShape>(&user_g);
+        // two nodes share the same position, one slightly overlaps them, one is far away
+        g.node_mut(idxs[0]).unwrap().set_location(Pos2::new(0., 0.));
+        g.node_mut(idxs[1]).unwrap().set_location(Pos2::new(0., 0.));
+        g.node_mut(idxs[2]).unwrap().set_location(Pos2::new(8., 0.));
+        g.node_mut(idxs[3])
+            .unwrap()
+            .set_location(Pos2::new(500., 500.));
+
+        assert!(declutter_positions(&mut g, 2.));
+
+        let min_dist = 2. * node_size(g.node(idxs[0]).unwrap(), Vec2::X) + 2.;
+        for (i, a) in idxs.iter().enumerate() {
+            for b in &idxs[i + 1..] {
+                let dist = g
+                    .node(*a)
+                    .unwrap()
+                    .location()
+                    .distance(g.node(*b).unwrap().location());
+                assert!(dist >= min_dist - 1e-2, "{a:?} and {b:?} overlap");
+            }
+        }
+        assert_eq!(g.node(idxs[3]).unwrap().location(), Pos2::new(500., 500.));
+    }
+
+    #[test]
+    fn test_degree_centrality() {
+        let mut user_g: StableGraph<_, _, Directed> = StableGraph::new();
+        let hub = user_g.add_node(());
+        let n1 = user_g.add_node(());
+        let n2 = user_g.add_node(());
+        let isolated = user_g.add_node(());
+        user_g.add_edge(hub, n1, ());
+        user_g.add_edge(n2, hub, ());
+
+        let g = to_graph::<_, _, _, _, DefaultNodeShape, DefaultEdgeShape>(&user_g);
+        let centrality = degree_centrality(&g);
+
+        assert_eq!(centrality[&hub], 1.);
+        assert_eq!(centrality[&n1], 0.5);
+        assert_eq!(centrality[&n2], 0.5);
+        assert_eq!(centrality[&isolated], 0.);
+    }
+
+    #[test]
+    fn test_size_nodes_by() {
+        let mut user_g: StableGraph<f32, ()> = StableGraph::new();
+        let light = user_g.add_node(1.);
+        let medium = user_g.add_node(2.);
+        let heavy = user_g.add_node(5.);
+
+        let mut g = to_graph::<_, _, _, _, DefaultNodeShape, DefaultEdgeShape>(&user_g);
+        size_nodes_by(&mut g, |weight| *weight, 4., 20.);
+
+        assert_eq!(g.node(light).unwrap().radius(), Some(4.));
+        assert_eq!(g.node(medium).unwrap().radius(), Some(8.));
+        assert_eq!(g.node(heavy).unwrap().radius(), Some(20.));
+
+        size_nodes_by(&mut g, |_| 1., 4., 20.);
+        assert_eq!(g.node(heavy).unwrap().radius(), Some(4.));
+    }
+
     #[test]
     fn test_to_graph_undirected() {
         let mut user_g: StableGraph<_, _, Undirected> = StableGraph::default();
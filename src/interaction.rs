@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// User interaction with the graph widget which can be recorded and replayed.
+///
+/// Positions and deltas are in screen coordinates of the widget.
+/// See [`crate::GraphView::with_interaction_log`] and [`crate::GraphView::replay`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Interaction {
+    Click {
+        pos: [f32; 2],
+    },
+    DoubleClick {
+        pos: [f32; 2],
+    },
+    /// Drag of the node found at `from` by `delta`.
+    NodeDrag {
+        from: [f32; 2],
+        delta: [f32; 2],
+    },
+    Zoom {
+        delta: f32,
+        center: Option<[f32; 2]>,
+    },
+    Pan {
+        delta: [f32; 2],
+    },
+}
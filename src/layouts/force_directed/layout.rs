@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+
+use egui::{Pos2, Vec2};
+use petgraph::{stable_graph::IndexType, EdgeType};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    layouts::{Layout, LayoutState},
+    DisplayEdge, DisplayNode, Graph,
+};
+
+/// Distance below which two nodes are considered to share a position.
+const MIN_DISTANCE: f32 = 0.01;
+
+/// Parameters of the [`ForceDirected`] layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceParams {
+    /// Distance between connected nodes at which attraction and repulsion are balanced.
+    pub ideal_length: f32,
+    /// Multiplier of the repulsive force between every pair of nodes.
+    pub repulsion: f32,
+    /// Maximum number of iterations.
+    pub iterations: usize,
+    /// Iterations stop early when no node moves further than this distance.
+    pub min_movement: f32,
+    /// Scales the repulsion between two nodes by the product of their degrees plus one, so
+    /// that hubs keep more room around them for their neighbors.
+    #[serde(default)]
+    pub degree_repulsion: bool,
+    /// Scale applied to edge weights before they weigh the attraction, see
+    /// [`ForceDirected::apply_weighted`].
+    #[serde(default)]
+    pub length_scale: LengthScale,
+}
+
+impl Default for ForceParams {
+    fn default() -> Self {
+        Self {
+            ideal_length: 50.,
+            repulsion: 1.,
+            iterations: 300,
+            min_movement: 0.1,
+            degree_repulsion: false,
+            length_scale: LengthScale::default(),
+        }
+    }
+}
+
+/// Maps edge weights of the [`ForceDirected`] layout, so that wide ranges of weights
+/// do not crush lighter edges next to the heaviest ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LengthScale {
+    /// Weights are used as they are.
+    #[default]
+    Linear,
+    /// Weights are mapped to `ln(1 + w)`.
+    Log,
+    /// Weights are mapped to `sqrt(w)`.
+    Sqrt,
+}
+
+impl LengthScale {
+    fn apply(self, weight: f32) -> f32 {
+        match self {
+            LengthScale::Linear => weight,
+            LengthScale::Log => weight.ln_1p(),
+            LengthScale::Sqrt => weight.sqrt(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct State {
+    triggered: bool,
+}
+
+impl LayoutState for State {}
+
+/// Places nodes with the Fruchterman-Reingold force-directed algorithm. Applies once
+/// with default [`ForceParams`], use [`ForceDirected::apply`] for custom parameters.
+#[derive(Debug, Default)]
+pub struct ForceDirected {
+    state: State,
+}
+
+impl ForceDirected {
+    /// Moves nodes apart by a repulsive force between every pair of nodes and together by
+    /// an attractive force along edges, ignoring edge directions.
+    ///
+    /// The maximal step of a node, the temperature, cools down linearly with iterations.
+    /// Dragged and pinned nodes are held fixed. Nodes sharing a position are pushed apart in distinct
+    /// directions.
+    pub fn apply<N, E, Ty, Ix, Dn, De>(g: &mut Graph<N, E, Ty, Ix, Dn, De>, params: &ForceParams)
+    where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        Self::apply_weighted(g, params, |_| 1.);
+    }
+
+    /// Like [`ForceDirected::apply`], but weighs the attraction along every edge with the
+    /// weight returned by `weight` for the edge payload.
+    ///
+    /// For nodes at distance `d`, with the ideal length `k`, an edge of weight `w` pulls with
+    /// `w * d^2 / k` while every pair of nodes is pushed apart with `repulsion * k^2 / d`.
+    /// Heavier edges therefore settle shorter and lighter ones allow more spread, so weakly
+    /// connected groups of nodes drift apart. Negative weights are treated as zero, the rest
+    /// are mapped with [`ForceParams::length_scale`]. With [`ForceParams::degree_repulsion`] the repulsion is multiplied by
+    /// `(degree_a + 1) * (degree_b + 1)`.
+    pub fn apply_weighted<N, E, Ty, Ix, Dn, De>(
+        g: &mut Graph<N, E, Ty, Ix, Dn, De>,
+        params: &ForceParams,
+        weight: impl Fn(&E) -> f32,
+    ) where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        let indices = g.g.node_indices().collect::<Vec<_>>();
+        let mut positions = indices
+            .iter()
+            .map(|idx| g.g[*idx].location())
+            .collect::<Vec<_>>();
+        let pinned = indices
+            .iter()
+            .map(|idx| g.g[*idx].dragged() || g.g[*idx].pinned())
+            .collect::<Vec<_>>();
+        let slots = indices
+            .iter()
+            .enumerate()
+            .map(|(i, idx)| (*idx, i))
+            .collect::<HashMap<_, _>>();
+        let edges =
+            g.g.edge_indices()
+                .filter_map(|e| {
+                    let (a, b) = g.g.edge_endpoints(e)?;
+                    let w = weight(g.g[e].payload()).max(0.);
+                    Some((slots[&a], slots[&b], params.length_scale.apply(w)))
+                })
+                .filter(|(a, b, _)| a != b)
+                .collect::<Vec<_>>();
+        let mut degrees = vec![0.; positions.len()];
+        for (a, b, _) in &edges {
+            degrees[*a] += 1.;
+            degrees[*b] += 1.;
+        }
+
+        let k = params.ideal_length;
+        let initial_temperature = k;
+        for iteration in 0..params.iterations {
+            let mut displacements = vec![Vec2::ZERO; positions.len()];
+
+            for i in 0..positions.len() {
+                for j in (i + 1)..positions.len() {
+                    let delta = separation(positions[i], positions[j], i * positions.len() + j);
+                    let mut force = params.repulsion * k * k / delta.length();
+                    if params.degree_repulsion {
+                        force *= (degrees[i] + 1.) * (degrees[j] + 1.);
+                    }
+                    let push = delta.normalized() * force;
+                    displacements[i] += push;
+                    displacements[j] -= push;
+                }
+            }
+
+            for (a, b, w) in &edges {
+                let delta = separation(positions[*a], positions[*b], a * positions.len() + b);
+                let pull = delta.normalized() * *w * delta.length_sq() / k;
+                displacements[*a] -= pull;
+                displacements[*b] += pull;
+            }
+
+            let temperature =
+                initial_temperature * (1. - iteration as f32 / params.iterations as f32);
+            let mut max_movement: f32 = 0.;
+            for (i, displacement) in displacements.iter().enumerate() {
+                if pinned[i] || displacement.length() == 0. {
+                    continue;
+                }
+                let step = displacement.normalized() * displacement.length().min(temperature);
+                positions[i] += step;
+                max_movement = max_movement.max(step.length());
+            }
+
+            if max_movement < params.min_movement {
+                break;
+            }
+        }
+
+        for ((idx, pos), pinned) in indices.iter().zip(positions).zip(pinned) {
+            if !pinned {
+                g.g[*idx].set_location(pos);
+            }
+        }
+    }
+}
+
+/// Returns the vector from `b` to `a`, or a short vector in a direction derived from `seed`
+/// if the nodes share a position.
+fn separation(a: Pos2, b: Pos2, seed: usize) -> Vec2 {
+    let delta = a - b;
+    if delta.length() < MIN_DISTANCE {
+        return Vec2::angled(seed as f32) * MIN_DISTANCE;
+    }
+    delta
+}
+
+impl Layout<State> for ForceDirected {
+    fn next<N, E, Ty, Ix, Dn, De>(&mut self, g: &mut Graph<N, E, Ty, Ix, Dn, De>)
+    where
+        N: Clone,
+        E: Clone,
+        Ty: EdgeType,
+        Ix: IndexType,
+        Dn: DisplayNode<N, E, Ty, Ix>,
+        De: DisplayEdge<N, E, Ty, Ix, Dn>,
+    {
+        if self.state.triggered {
+            return;
+        }
+
+        Self::apply(g, &ForceParams::default());
+
+        self.state.triggered = true;
+    }
+
+    fn state(&self) -> State {
+        self.state.clone()
+    }
+
+    fn from_state(state: State) -> impl Layout<State> {
+        Self { state }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::stable_graph::StableGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_force_directed() {
+        let mut g: Graph = Graph::new(StableGraph::default());
+        let a = g.add_node_with_location((), Pos2::new(0., 0.));
+        let b = g.add_node_with_location((), Pos2::new(1., 0.));
+        let c = g.add_node_with_location((), Pos2::new(1000., 0.));
+        let pinned = g.add_node_with_location((), Pos2::new(0., 0.));
+        let anchor = g.add_node_with_location((), Pos2::new(1., 1.));
+        g.add_edge(a, b, ());
+        g.add_edge(b, c, ());
+        g.add_edge(c, anchor, ());
+        g.node_mut(pinned).unwrap().set_dragged(true);
+        g.node_mut(anchor).unwrap().set_pinned(true);
+
+        let params = ForceParams::default();
+        ForceDirected::apply(&mut g, &params);
+
+        let loc = |idx| g.node(idx).unwrap().location();
+        assert_eq!(loc(pinned), Pos2::new(0., 0.));
+        assert_eq!(loc(anchor), Pos2::new(1., 1.));
+        // connected nodes end up close to the ideal length, neither collapsed nor far apart
+        for (x, y) in [(a, b), (b, c)] {
+            let dist = loc(x).distance(loc(y));
+            assert!(dist > params.ideal_length / 4. && dist < params.ideal_length * 4.);
+        }
+        assert!(loc(a).distance(loc(pinned)) > params.ideal_length / 4.);
+    }
+
+    #[test]
+    fn test_heavier_edges_pull_closer() {
+        let mut g: Graph<(), f32> = Graph::new(StableGraph::default());
+        let center = g.add_node_with_location((), Pos2::new(0., 0.));
+        let light = g.add_node_with_location((), Pos2::new(100., 0.));
+        let heavy = g.add_node_with_location((), Pos2::new(-100., 0.));
+        g.add_edge(center, light, 1.);
+        g.add_edge(center, heavy, 10.);
+
+        ForceDirected::apply_weighted(&mut g, &ForceParams::default(), |w| *w);
+
+        let dist = |idx| {
+            g.node(center)
+                .unwrap()
+                .location()
+                .distance(g.node(idx).unwrap().location())
+        };
+        assert!(dist(heavy) * 1.5 < dist(light));
+    }
+
+    #[test]
+    fn test_log_scale_compresses_heavy_edges() {
+        // ratio of the distances to the nodes joined by a light and by a heavy edge
+        let spread = |length_scale| {
+            let mut g: Graph<(), f32> = Graph::new(StableGraph::default());
+            let center = g.add_node_with_location((), Pos2::new(0., 0.));
+            let light = g.add_node_with_location((), Pos2::new(100., 0.));
+            let heavy = g.add_node_with_location((), Pos2::new(-100., 0.));
+            g.add_edge(center, light, 1.);
+            g.add_edge(center, heavy, 1000.);
+
+            let params = ForceParams {
+                length_scale,
+                ..Default::default()
+            };
+            ForceDirected::apply_weighted(&mut g, &params, |w| *w);
+
+            let dist = |idx| {
+                g.node(center)
+                    .unwrap()
+                    .location()
+                    .distance(g.node(idx).unwrap().location())
+            };
+            dist(light) / dist(heavy)
+        };
+
+        let linear = spread(LengthScale::Linear);
+        let sqrt = spread(LengthScale::Sqrt);
+        let log = spread(LengthScale::Log);
+        assert!(linear > sqrt && sqrt > log);
+        assert!(log > 1.);
+    }
+
+    /// Lays out two clusters of densely connected nodes joined by a single edge, with nodes
+    /// of both clusters initially interleaved. Returns the largest distance within a cluster
+    /// and the smallest distance between the clusters.
+    fn clusters(inner_weight: f32, bridge_weight: f32) -> (f32, f32) {
+        let mut g: Graph<(), f32> = Graph::new(StableGraph::default());
+        let mut groups = [vec![], vec![]];
+        for i in 0..8 {
+            let loc = Pos2::new(i as f32 * 10., (i % 2) as f32 * 5.);
+            groups[i % 2].push(g.add_node_with_location((), loc));
+        }
+        for cluster in &groups {
+            for (i, a) in cluster.iter().enumerate() {
+                for b in &cluster[i + 1..] {
+                    g.add_edge(*a, *b, inner_weight);
+                }
+            }
+        }
+        g.add_edge(groups[0][0], groups[1][0], bridge_weight);
+
+        ForceDirected::apply_weighted(&mut g, &ForceParams::default(), |w| *w);
+
+        let dist = |a, b| {
+            g.node(a)
+                .unwrap()
+                .location()
+                .distance(g.node(b).unwrap().location())
+        };
+        let within = groups
+            .iter()
+            .flat_map(|c| c.iter().flat_map(|a| c.iter().map(|b| dist(*a, *b))))
+            .fold(0., f32::max);
+        let between = groups[0]
+            .iter()
+            .flat_map(|a| groups[1].iter().map(|b| dist(*a, *b)))
+            .fold(f32::INFINITY, f32::min);
+        (within, between)
+    }
+
+    #[test]
+    fn test_weighted_clusters() {
+        let (within, between) = clusters(10., 0.1);
+        assert!(between > within * 3.);
+
+        // with uniform weights the clusters are not told apart
+        let (within, between) = clusters(1., 1.);
+        assert!(between < within * 3.);
+    }
+}
@@ -0,0 +1,3 @@
+mod layout;
+
+pub use layout::{ForceDirected, ForceParams, LengthScale, State};
@@ -1,26 +1,41 @@
 mod draw;
 mod elements;
+mod export;
 mod graph;
 mod graph_view;
 mod helpers;
+mod interaction;
 mod layouts;
 mod metadata;
 mod settings;
+mod spatial_index;
 
-pub use draw::{DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode, DrawContext};
-pub use elements::{Edge, EdgeProps, Node, NodeProps};
+pub use draw::{
+    Annotation, DefaultEdgeShape, DefaultNodeShape, DisplayEdge, DisplayNode, DrawContext, Legend,
+    LegendShape,
+};
+pub use elements::{Edge, EdgeProps, EdgeStyle, Node, NodeProps, NodeShape};
 pub use graph::Graph;
 pub use graph_view::{DefaultGraphView, GraphView};
 pub use helpers::{
-    add_edge, add_edge_custom, add_node, add_node_custom, default_edge_transform,
-    default_node_transform, node_size, random_graph, to_graph, to_graph_custom,
+    add_edge, add_edge_custom, add_node, add_node_custom, declutter_positions,
+    default_edge_transform, default_node_transform, degree_centrality, layout_circular,
+    layout_grid, layout_radial, node_size, random_graph, size_nodes_by, to_graph, to_graph_custom,
+};
+pub use interaction::Interaction;
+pub use layouts::force_directed::{
+    ForceDirected as LayoutForceDirected, ForceParams, LengthScale,
+    State as LayoutStateForceDirected,
 };
 pub use layouts::hierarchical::{
     Hierarchical as LayoutHierarchical, State as LayoutStateHierarchical,
 };
 pub use layouts::random::{Random as LayoutRandom, State as LayoutStateRandom};
 pub use metadata::Metadata;
-pub use settings::{SettingsInteraction, SettingsNavigation, SettingsStyle};
+pub use settings::{
+    Corner, CurveSegments, DrawOrder, LabelBackground, SelectionBox, SettingsInteraction,
+    SettingsNavigation, SettingsStyle,
+};
 
 #[cfg(feature = "events")]
 pub mod events;
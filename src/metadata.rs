@@ -1,4 +1,6 @@
-use egui::{Id, Pos2, Rect, Vec2};
+use std::collections::HashMap;
+
+use egui::{epaint::text::Fonts, Color32, FontFamily, FontId, Id, Pos2, Rect, Vec2};
 use petgraph::{stable_graph::IndexType, EdgeType};
 use serde::{Deserialize, Serialize};
 
@@ -6,6 +8,18 @@ use crate::{node_size, DisplayNode, Node};
 
 const KEY: &str = "egui_graphs_metadata";
 
+/// Distance to the camera target below which an animation snaps to the target and stops.
+const CAMERA_EPSILON: f32 = 1e-3;
+
+/// Distance in canvas units below which a smoothed node location snaps to the actual one.
+const LOCATION_EPSILON: f32 = 1e-2;
+
+/// Rate per second at which the pan velocity decays exponentially after a pan drag.
+const PAN_INERTIA_DECAY: f32 = 4.;
+
+/// Speed in screen points per second below which gliding after a pan drag stops.
+const PAN_INERTIA_MIN_SPEED: f32 = 10.;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct Bounds {
     min: Vec2,
@@ -22,6 +36,12 @@ impl Default for Bounds {
 }
 
 impl Bounds {
+    /// Returns the bounding rect or `None` if no node was visited.
+    fn rect(&self) -> Option<Rect> {
+        (self.min.x <= self.max.x && self.min.y <= self.max.y)
+            .then(|| Rect::from_min_max(self.min.to_pos2(), self.max.to_pos2()))
+    }
+
     pub fn compute_next<
         N: Clone,
         E: Clone,
@@ -34,8 +54,8 @@ impl Bounds {
     ) {
         let size = node_size(n, Vec2::new(0., 1.));
         let loc = n.location();
-        if loc.x + size < self.min.x {
-            self.min.x = loc.x + size;
+        if loc.x - size < self.min.x {
+            self.min.x = loc.x - size;
         };
         if loc.x + size > self.max.x {
             self.max.x = loc.x + size;
@@ -49,6 +69,14 @@ impl Bounds {
     }
 }
 
+/// State of the view shared between frames, like the camera pan and zoom.
+///
+/// The metadata implements [`Serialize`] and [`Deserialize`], so the view can be persisted
+/// between sessions. `egui` serializes [`Vec2`], [`Pos2`] and [`Rect`] with its `persistence`
+/// feature, which this crate always enables. Take the metadata with [`Metadata::load`] after
+/// the widget is drawn, serialize it and after restoring put it back with [`Metadata::save`]
+/// before the widget is drawn. Transient state like camera animations and node smoothing
+/// is not serialized.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Metadata {
     /// Whether the frame is the first one
@@ -59,9 +87,40 @@ pub struct Metadata {
     pub pan: Vec2,
     /// Top left position of widget
     pub top_left: Pos2,
+    /// Rect of the widget in the last frame
+    pub rect: Rect,
 
     /// State of bounds iteration
     bounds: Bounds,
+    /// Whether the view is fitted to the graph on the next frame with a non-empty graph
+    #[serde(default)]
+    fit_pending: bool,
+    /// Smoothed locations at which nodes are rendered, by node index
+    #[serde(skip)]
+    rendered_locations: HashMap<usize, Pos2>,
+    /// Pan and zoom the view is animated towards
+    #[serde(skip)]
+    camera_target: Option<(Vec2, f32)>,
+    /// Velocity of the pan in screen points per second used to glide after a pan drag
+    #[serde(skip)]
+    pan_velocity: Vec2,
+    /// Start and end of the selection box being dragged, in canvas coordinates
+    #[serde(skip)]
+    selection_box: Option<(Pos2, Pos2)>,
+    /// Start and end of the zoom box being dragged, in canvas coordinates
+    #[serde(skip)]
+    zoom_box: Option<(Pos2, Pos2)>,
+    /// Index of the source node and the pointer position in canvas coordinates of the edge
+    /// being created
+    #[serde(skip)]
+    edge_draft: Option<(usize, Pos2)>,
+    /// Whether the view is being moved with the pointer pressed in the minimap
+    #[serde(skip)]
+    minimap_drag: bool,
+    /// Pointer position in canvas coordinates and locations of the dragged nodes at the
+    /// start of a node drag
+    #[serde(skip)]
+    drag_origin: Option<(Pos2, Vec<(usize, Pos2)>)>,
 }
 
 impl Default for Metadata {
@@ -71,12 +130,24 @@ impl Default for Metadata {
             zoom: 1.,
             pan: Vec2::default(),
             top_left: Pos2::default(),
+            rect: Rect::NOTHING,
             bounds: Bounds::default(),
+            fit_pending: false,
+            rendered_locations: HashMap::default(),
+            camera_target: Option::default(),
+            pan_velocity: Vec2::default(),
+            selection_box: Option::default(),
+            zoom_box: Option::default(),
+            edge_draft: Option::default(),
+            minimap_drag: false,
+            drag_origin: Option::default(),
         }
     }
 }
 
 impl Metadata {
+    /// Returns the metadata of the last frame stored in the `egui` memory, or the default
+    /// metadata if the widget was not drawn yet.
     pub fn load(ui: &egui::Ui) -> Self {
         ui.data_mut(|data| {
             data.get_persisted::<Metadata>(Id::new(KEY))
@@ -84,12 +155,20 @@ impl Metadata {
         })
     }
 
+    /// Stores the metadata in the `egui` memory, where the widget picks it up in the next frame.
+    ///
+    /// Metadata saved after the first frame keeps the widget from fitting the graph to the
+    /// screen initially, so a restored view is shown as it was.
     pub fn save(self, ui: &mut egui::Ui) {
         ui.data_mut(|data| {
             data.insert_persisted(Id::new(KEY), self);
         });
     }
 
+    /// Converts a position in graph space to a position on the screen.
+    ///
+    /// Load the metadata of the last frame with [`Metadata::load`] to place overlays
+    /// or tooltips next to nodes. This is the inverse of [`Metadata::screen_to_canvas_pos`].
     pub fn canvas_to_screen_pos(&self, pos: Pos2) -> Pos2 {
         (pos.to_vec2() * self.zoom + self.pan).to_pos2()
     }
@@ -98,6 +177,17 @@ impl Metadata {
         size * self.zoom
     }
 
+    /// Converts a rect in graph space to the screen, see [`Metadata::canvas_to_screen_pos`].
+    pub fn canvas_to_screen_rect(&self, rect: Rect) -> Rect {
+        Rect::from_min_max(
+            self.canvas_to_screen_pos(rect.min),
+            self.canvas_to_screen_pos(rect.max),
+        )
+    }
+
+    /// Converts a position on the screen, e.g. the pointer position, to a position in graph space.
+    ///
+    /// This is the inverse of [`Metadata::canvas_to_screen_pos`].
     pub fn screen_to_canvas_pos(&self, pos: Pos2) -> Pos2 {
         ((pos.to_vec2() - self.pan) / self.zoom).to_pos2()
     }
@@ -115,13 +205,414 @@ impl Metadata {
         self.bounds.compute_next(n);
     }
 
-    /// Returns bounding rect of the graph.
-    pub fn graph_bounds(&self) -> Rect {
-        Rect::from_min_max(self.bounds.min.to_pos2(), self.bounds.max.to_pos2())
+    /// Returns bounding rect of the graph or `None` if the graph is empty.
+    pub fn graph_bounds(&self) -> Option<Rect> {
+        self.bounds.rect()
     }
 
     /// Resets the bounds iterator.
     pub fn reset_bounds(&mut self) {
         self.bounds = Bounds::default();
     }
+
+    pub(crate) fn set_camera_target(&mut self, pan: Vec2, zoom: f32) {
+        self.camera_target = Some((pan, zoom));
+        self.pan_velocity = Vec2::ZERO;
+    }
+
+    pub(crate) fn clear_camera_target(&mut self) {
+        self.camera_target = None;
+    }
+
+    pub(crate) fn camera_animating(&self) -> bool {
+        self.camera_target.is_some()
+    }
+
+    /// Returns pan and zoom moved towards the camera target by the fraction `t`,
+    /// or `None` if there is no target.
+    ///
+    /// When the target is reached it is returned exactly and the animation stops.
+    pub(crate) fn step_camera(&mut self, t: f32) -> Option<(Vec2, f32)> {
+        let (pan, zoom) = self.camera_target?;
+
+        let next_pan = self.pan + (pan - self.pan) * t;
+        let next_zoom = self.zoom + (zoom - self.zoom) * t;
+        let arrived = (pan - next_pan).length() < CAMERA_EPSILON
+            && (zoom - next_zoom).abs() < CAMERA_EPSILON * zoom;
+        if arrived {
+            self.camera_target = None;
+            return Some((pan, zoom));
+        }
+
+        Some((next_pan, next_zoom))
+    }
+
+    /// Blends the velocity of the pan drag moving the view by `delta` in the frame of `dt` seconds
+    /// into the pan velocity, which smooths out uneven frames.
+    pub(crate) fn track_pan_velocity(&mut self, delta: Vec2, dt: f32) {
+        if dt > 0. {
+            self.pan_velocity = (self.pan_velocity + delta / dt) / 2.;
+        }
+    }
+
+    pub(crate) fn stop_pan_velocity(&mut self) {
+        self.pan_velocity = Vec2::ZERO;
+    }
+
+    /// Returns the pan delta of gliding in the frame of `dt` seconds and slows the gliding
+    /// down, or `None` once the view is not gliding.
+    /// Whether the view keeps gliding after a pan drag, if pan inertia is enabled.
+    pub(crate) fn gliding(&self) -> bool {
+        self.pan_velocity.length() >= PAN_INERTIA_MIN_SPEED
+    }
+
+    pub(crate) fn step_pan_inertia(&mut self, dt: f32) -> Option<Vec2> {
+        if self.pan_velocity.length() < PAN_INERTIA_MIN_SPEED {
+            self.pan_velocity = Vec2::ZERO;
+            return None;
+        }
+
+        let delta = self.pan_velocity * dt;
+        self.pan_velocity *= (-PAN_INERTIA_DECAY * dt).exp();
+        Some(delta)
+    }
+
+    pub(crate) fn start_selection_box(&mut self, pos: Pos2) {
+        self.selection_box = Some((pos, pos));
+    }
+
+    /// Moves the free corner of the selection box, if there is one.
+    pub(crate) fn update_selection_box(&mut self, pos: Pos2) {
+        if let Some((_, end)) = &mut self.selection_box {
+            *end = pos;
+        }
+    }
+
+    /// Returns the selection box being dragged in canvas coordinates.
+    pub(crate) fn selection_box(&self) -> Option<Rect> {
+        self.selection_box
+            .map(|(start, end)| Rect::from_two_pos(start, end))
+    }
+
+    pub(crate) fn take_selection_box(&mut self) -> Option<Rect> {
+        let rect = self.selection_box();
+        self.selection_box = None;
+        rect
+    }
+
+    pub(crate) fn start_zoom_box(&mut self, pos: Pos2) {
+        self.zoom_box = Some((pos, pos));
+    }
+
+    /// Moves the free corner of the zoom box, if there is one.
+    pub(crate) fn update_zoom_box(&mut self, pos: Pos2) {
+        if let Some((_, end)) = &mut self.zoom_box {
+            *end = pos;
+        }
+    }
+
+    /// Returns the zoom box being dragged in canvas coordinates.
+    pub(crate) fn zoom_box(&self) -> Option<Rect> {
+        self.zoom_box
+            .map(|(start, end)| Rect::from_two_pos(start, end))
+    }
+
+    pub(crate) fn take_zoom_box(&mut self) -> Option<Rect> {
+        let rect = self.zoom_box();
+        self.zoom_box = None;
+        rect
+    }
+
+    pub(crate) fn start_edge_draft(&mut self, source: usize, pos: Pos2) {
+        self.edge_draft = Some((source, pos));
+    }
+
+    /// Moves the free end of the edge being created, if there is one.
+    pub(crate) fn update_edge_draft(&mut self, pos: Pos2) {
+        if let Some((_, end)) = &mut self.edge_draft {
+            *end = pos;
+        }
+    }
+
+    /// Returns the source node index and the free end in canvas coordinates of the edge being
+    /// created.
+    pub(crate) fn edge_draft(&self) -> Option<(usize, Pos2)> {
+        self.edge_draft
+    }
+
+    pub(crate) fn take_edge_draft(&mut self) -> Option<(usize, Pos2)> {
+        self.edge_draft.take()
+    }
+
+    pub(crate) fn set_minimap_drag(&mut self, dragging: bool) {
+        self.minimap_drag = dragging;
+    }
+
+    pub(crate) fn minimap_drag(&self) -> bool {
+        self.minimap_drag
+    }
+
+    pub(crate) fn start_node_drag(&mut self, pointer: Pos2, nodes: Vec<(usize, Pos2)>) {
+        self.drag_origin = Some((pointer, nodes));
+    }
+
+    pub(crate) fn drag_origin(&self) -> Option<&(Pos2, Vec<(usize, Pos2)>)> {
+        self.drag_origin.as_ref()
+    }
+
+    pub(crate) fn clear_node_drag(&mut self) {
+        self.drag_origin = None;
+    }
+
+    pub(crate) fn fit_pending(&self) -> bool {
+        self.fit_pending
+    }
+
+    pub(crate) fn set_fit_pending(&mut self, pending: bool) {
+        self.fit_pending = pending;
+    }
+
+    /// Moves rendered locations of nodes towards their actual locations by the fraction `t`.
+    ///
+    /// Nodes with `snap` set, and nodes rendered for the first time, are rendered at their
+    /// actual locations. Nodes missing in `nodes` are forgotten.
+    ///
+    /// Returns whether any node is still on its way to its actual location.
+    pub(crate) fn lerp_locations(
+        &mut self,
+        nodes: impl Iterator<Item = (usize, Pos2, bool)>,
+        t: f32,
+    ) -> bool {
+        let prev = std::mem::take(&mut self.rendered_locations);
+        let mut moving = false;
+        self.rendered_locations = nodes
+            .map(|(idx, target, snap)| {
+                let loc = match prev.get(&idx) {
+                    Some(prev) if !snap => prev.lerp(target, t),
+                    _ => target,
+                };
+                if loc.distance(target) < LOCATION_EPSILON {
+                    return (idx, target);
+                }
+                moving = true;
+                (idx, loc)
+            })
+            .collect();
+
+        moving
+    }
+
+    /// Stops smoothing of node locations.
+    pub(crate) fn clear_rendered_locations(&mut self) {
+        self.rendered_locations.clear();
+    }
+
+    /// Returns the smoothed location at which the node is rendered, if smoothing is enabled.
+    pub(crate) fn rendered_location(&self, idx: usize) -> Option<Pos2> {
+        self.rendered_locations.get(&idx).copied()
+    }
+}
+
+/// Returns bounding rect of the given nodes or `None` if there are no nodes.
+pub(crate) fn nodes_bounds<'n, N, E, Ty, Ix, D>(
+    nodes: impl IntoIterator<Item = &'n Node<N, E, Ty, Ix, D>>,
+) -> Option<Rect>
+where
+    N: Clone + 'n,
+    E: Clone + 'n,
+    Ty: EdgeType + 'n,
+    Ix: IndexType,
+    D: DisplayNode<N, E, Ty, Ix> + 'n,
+{
+    let mut bounds = Bounds::default();
+    for n in nodes {
+        bounds.compute_next(n);
+    }
+
+    bounds.rect()
+}
+
+/// Returns the bounds of the node labels in canvas coordinates, as they are placed by
+/// [`crate::DefaultNodeShape`], or `None` if no node has a label.
+///
+/// The label font size scales with the zoom like the node radius does, so the bounds do not
+/// depend on the zoom.
+pub(crate) fn labels_bounds<'n, N, E, Ty, Ix, D>(
+    fonts: &Fonts,
+    nodes: impl IntoIterator<Item = &'n Node<N, E, Ty, Ix, D>>,
+) -> Option<Rect>
+where
+    N: Clone + 'n,
+    E: Clone + 'n,
+    Ty: EdgeType + 'n,
+    Ix: IndexType,
+    D: DisplayNode<N, E, Ty, Ix> + 'n,
+{
+    nodes
+        .into_iter()
+        .filter(|n| !n.props().label.is_empty())
+        .map(|n| {
+            let radius = node_size(n, Vec2::new(0., 1.));
+            let galley = fonts.layout_no_wrap(
+                n.label(),
+                FontId::new(radius, FontFamily::Monospace),
+                Color32::PLACEHOLDER,
+            );
+            let loc = n.location();
+            let min = Pos2::new(loc.x - galley.size().x / 2., loc.y - radius * 2.);
+            Rect::from_min_size(min, galley.size())
+        })
+        .reduce(Rect::union)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_canvas_round_trip() {
+        let points = [Pos2::ZERO, Pos2::new(12.5, -3.), Pos2::new(-1000., 250.)];
+        let views = [
+            (1., Vec2::ZERO),
+            (0.01, Vec2::new(-40., 15.)),
+            (3.7, Vec2::new(100., -250.)),
+            (250., Vec2::new(0.5, 0.5)),
+        ];
+
+        for (zoom, pan) in views {
+            let meta = Metadata {
+                zoom,
+                pan,
+                ..Default::default()
+            };
+            for p in points {
+                let screen = meta.canvas_to_screen_pos(meta.screen_to_canvas_pos(p));
+                let canvas = meta.screen_to_canvas_pos(meta.canvas_to_screen_pos(p));
+                let tolerance = 1e-3 * (1. + p.to_vec2().length() + pan.length());
+                assert!(screen.distance(p) < tolerance, "{screen:?} != {p:?}");
+                assert!(canvas.distance(p) < tolerance, "{canvas:?} != {p:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_save_round_trip() {
+        let ctx = egui::Context::default();
+        let saved = Metadata {
+            first_frame: false,
+            zoom: 2.5,
+            pan: Vec2::new(-30., 12.),
+            ..Default::default()
+        };
+
+        let mut loaded = None;
+        for _ in 0..2 {
+            let _ = ctx.run(egui::RawInput::default(), |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    if loaded.is_none() {
+                        saved.clone().save(ui);
+                        loaded = Some(None);
+                    } else {
+                        loaded = Some(Some(Metadata::load(ui)));
+                    }
+                });
+            });
+        }
+
+        let loaded = loaded.flatten().unwrap();
+        assert!(!loaded.first_frame);
+        assert_eq!(loaded.zoom, saved.zoom);
+        assert_eq!(loaded.pan, saved.pan);
+    }
+
+    #[test]
+    fn test_step_pan_inertia() {
+        let mut meta = Metadata::default();
+        assert_eq!(meta.step_pan_inertia(0.1), None);
+
+        meta.track_pan_velocity(Vec2::new(20., 0.), 0.01);
+        meta.track_pan_velocity(Vec2::new(20., 0.), 0.01);
+        assert_eq!(meta.pan_velocity, Vec2::new(1500., 0.));
+
+        let mut glided = Vec2::ZERO;
+        let mut steps = 0;
+        while let Some(delta) = meta.step_pan_inertia(1. / 60.) {
+            glided += delta;
+            steps += 1;
+            assert!(steps < 1000, "gliding does not stop");
+        }
+        // the glide is bounded by velocity / decay
+        assert!(glided.x > 0. && glided.x < 1500. / PAN_INERTIA_DECAY + 25.);
+        assert_eq!(glided.y, 0.);
+
+        meta.track_pan_velocity(Vec2::new(20., 0.), 0.01);
+        meta.stop_pan_velocity();
+        assert_eq!(meta.step_pan_inertia(1. / 60.), None);
+    }
+
+    #[test]
+    fn test_step_camera() {
+        let mut meta = Metadata::default();
+        assert_eq!(meta.step_camera(0.5), None);
+
+        meta.set_camera_target(Vec2::new(10., -10.), 2.);
+        let (pan, zoom) = meta.step_camera(0.5).unwrap();
+        assert_eq!(pan, Vec2::new(5., -5.));
+        assert_eq!(zoom, 1.5);
+
+        let mut steps = 0;
+        while let Some((pan, zoom)) = meta.step_camera(0.5) {
+            meta.pan = pan;
+            meta.zoom = zoom;
+            steps += 1;
+            assert!(steps < 100, "animation does not stop");
+        }
+        assert_eq!(meta.pan, Vec2::new(10., -10.));
+        assert_eq!(meta.zoom, 2.);
+    }
+
+    #[test]
+    fn test_lerp_locations() {
+        let mut meta = Metadata::default();
+        let nodes = |x: f32| [(0, Pos2::new(x, 0.), false), (1, Pos2::new(x, 0.), true)];
+
+        meta.lerp_locations(nodes(0.).into_iter(), 0.5);
+        assert_eq!(meta.rendered_location(0), Some(Pos2::new(0., 0.)));
+
+        meta.lerp_locations(nodes(10.).into_iter(), 0.5);
+        assert_eq!(meta.rendered_location(0), Some(Pos2::new(5., 0.)));
+        assert_eq!(meta.rendered_location(1), Some(Pos2::new(10., 0.)));
+
+        assert!(meta.lerp_locations(nodes(10.).into_iter().take(1), 0.5));
+        assert_eq!(meta.rendered_location(0), Some(Pos2::new(7.5, 0.)));
+        assert_eq!(meta.rendered_location(1), None);
+
+        // locations settle eventually so that the widget stops repainting
+        let mut steps = 0;
+        while meta.lerp_locations(nodes(10.).into_iter(), 0.5) {
+            steps += 1;
+        }
+        assert!(steps < 20);
+        assert_eq!(meta.rendered_location(0), Some(Pos2::new(10., 0.)));
+    }
+
+    #[test]
+    fn test_labels_bounds() {
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |_| {});
+
+        let unlabeled: Node<(), ()> = Node::new(());
+        let labeled: Node<(), ()> = Node::new(()).with_label("a long node label".to_string());
+        let radius = node_size(&labeled, Vec2::new(0., 1.));
+
+        ctx.fonts(|f| {
+            assert_eq!(labels_bounds(f, [&unlabeled]), None);
+
+            let bounds = labels_bounds(f, [&unlabeled, &labeled]).unwrap();
+            assert_eq!(bounds.min.y, -radius * 2.);
+            assert_eq!(bounds.center().x, 0.);
+            // the label is wider than the node, so fitting the nodes alone would cut it off
+            assert!(bounds.width() > radius * 2.);
+        });
+    }
 }
@@ -1,13 +1,53 @@
+use egui::{Color32, Key, PointerButton, Pos2, Rect, Vec2, Visuals};
+
+use crate::Legend;
+
 /// Represents graph interaction settings.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct SettingsInteraction {
     pub(crate) dragging_enabled: bool,
     pub(crate) node_clicking_enabled: bool,
+    pub(crate) node_double_clicking_enabled: bool,
+    pub(crate) node_secondary_clicking_enabled: bool,
+    pub(crate) deletion_enabled: bool,
+    pub(crate) edge_creation_enabled: bool,
     pub(crate) node_selection_enabled: bool,
     pub(crate) node_selection_multi_enabled: bool,
     pub(crate) edge_clicking_enabled: bool,
     pub(crate) edge_selection_enabled: bool,
     pub(crate) edge_selection_multi_enabled: bool,
+    pub(crate) highlight_incident_edges: bool,
+    pub(crate) max_group_drag: Option<usize>,
+    pub(crate) snap_to_grid: Option<f32>,
+    pub(crate) node_hover_enabled: bool,
+    pub(crate) edge_hover_enabled: bool,
+    pub(crate) edge_select_tolerance: f32,
+    pub(crate) select_all_shortcut: bool,
+}
+
+impl Default for SettingsInteraction {
+    fn default() -> Self {
+        Self {
+            dragging_enabled: false,
+            node_clicking_enabled: false,
+            node_double_clicking_enabled: false,
+            node_secondary_clicking_enabled: false,
+            deletion_enabled: false,
+            edge_creation_enabled: false,
+            node_selection_enabled: false,
+            node_selection_multi_enabled: false,
+            edge_clicking_enabled: false,
+            edge_selection_enabled: false,
+            edge_selection_multi_enabled: false,
+            highlight_incident_edges: false,
+            max_group_drag: Option::default(),
+            snap_to_grid: Option::default(),
+            node_hover_enabled: false,
+            edge_hover_enabled: false,
+            edge_select_tolerance: 3.,
+            select_all_shortcut: true,
+        }
+    }
 }
 
 impl SettingsInteraction {
@@ -24,7 +64,7 @@ impl SettingsInteraction {
         self
     }
 
-    /// Allows clicking on nodes.
+    /// Allows clicking on nodes, enables double clicks.
     ///
     /// Default: `false`
     pub fn with_node_clicking_enabled(mut self, enabled: bool) -> Self {
@@ -32,6 +72,57 @@ impl SettingsInteraction {
         self
     }
 
+    /// Allows double clicking on nodes without enabling single clicks.
+    ///
+    /// The first click of a double click is handled as a single click. The second one is
+    /// handled only as a double click: it does not toggle the selection of the node again.
+    ///
+    /// Default: `false`
+    pub fn with_node_double_clicking_enabled(mut self, enabled: bool) -> Self {
+        self.node_double_clicking_enabled = enabled;
+        self
+    }
+
+    /// Allows secondary (right) clicking on nodes, e.g. to open a context menu.
+    ///
+    /// The clicked node is available with [`crate::Graph::secondary_clicked_node`].
+    /// Secondary clicks on empty space are ignored.
+    ///
+    /// Default: `false`
+    pub fn with_node_secondary_clicking_enabled(mut self, enabled: bool) -> Self {
+        self.node_secondary_clicking_enabled = enabled;
+        self
+    }
+
+    /// Requests deletion of the selected nodes and edges when Delete or Backspace is pressed
+    /// while the widget has focus. The widget gets focus when it is clicked or dragged.
+    ///
+    /// Deletion is only requested with the `NodeDelete` and `EdgeDelete` events, so it
+    /// requires the `events` feature. The
+    /// graph is left untouched and the caller is responsible for removing the elements with
+    /// [`crate::Graph::remove_node`] and [`crate::Graph::remove_edge`], which return the removed
+    /// elements with their payloads, e.g. to undo the deletion.
+    ///
+    /// Default: `false`
+    pub fn with_deletion_enabled(mut self, enabled: bool) -> Self {
+        self.deletion_enabled = enabled;
+        self
+    }
+
+    /// Requests creation of an edge when a node is dragged onto another node while holding alt.
+    ///
+    /// Holding alt keeps dragging nodes and panning working as usual without it. While the
+    /// pointer is dragged a provisional edge is drawn from the source node to the pointer.
+    /// Releasing it over another node publishes the `EdgeCreate` event, so it requires the
+    /// `events` feature. Releasing it over empty space or over the source node does nothing.
+    /// The graph is left untouched and the caller is responsible for adding the edge.
+    ///
+    /// Default: `false`
+    pub fn with_edge_creation_enabled(mut self, enabled: bool) -> Self {
+        self.edge_creation_enabled = enabled;
+        self
+    }
+
     /// Selects clicked node, enables clicks.
     ///
     /// Select by clicking on node, deselect by clicking again.
@@ -46,6 +137,8 @@ impl SettingsInteraction {
 
     /// Multiselection for nodes, enables click and select.
     ///
+    /// Dragging over empty space while holding shift selects all nodes inside of the dragged box.
+    ///
     /// Default: `false`
     pub fn with_node_selection_multi_enabled(mut self, enabled: bool) -> Self {
         self.node_selection_multi_enabled = enabled;
@@ -79,15 +172,113 @@ impl SettingsInteraction {
         self.edge_selection_multi_enabled = enabled;
         self
     }
+
+    /// Selects all nodes with ctrl + A and inverts the selection of nodes with ctrl + I while
+    /// the widget has focus. Edges are included if their multiselection is enabled.
+    ///
+    /// Works only with [`SettingsInteraction::with_node_selection_multi_enabled`]. Disable it
+    /// if the app binds these shortcuts elsewhere.
+    ///
+    /// Default: `true`
+    pub fn with_select_all_shortcut(mut self, enabled: bool) -> Self {
+        self.select_all_shortcut = enabled;
+        self
+    }
+
+    /// Highlights edges connected to the selected nodes, so it is immediately visible
+    /// what a selected node connects to. The highlight is lighter than the edge selection.
+    ///
+    /// Default: `false`
+    pub fn with_highlight_incident_edges(mut self, enabled: bool) -> Self {
+        self.highlight_incident_edges = enabled;
+        self
+    }
+
+    /// Maximum number of selected nodes which are dragged together.
+    ///
+    /// Dragging a node of a multiselection moves the whole selection. When the selection is
+    /// larger than this limit, only the dragged node is moved to keep dragging responsive
+    /// on huge selections.
+    ///
+    /// Default: `None` (no limit)
+    pub fn with_max_group_drag(mut self, max: Option<usize>) -> Self {
+        self.max_group_drag = max;
+        self
+    }
+
+    /// Size of the grid cell in canvas coordinates dragged nodes snap to.
+    ///
+    /// Dragged nodes jump between the grid points closest to the pointer, so the grid stays
+    /// fixed regardless of the zoom. The grid is drawn while snapping is enabled.
+    ///
+    /// Default: `None`
+    pub fn with_snap_to_grid(mut self, cell: Option<f32>) -> Self {
+        self.snap_to_grid = cell.filter(|cell| *cell > 0.);
+        self
+    }
+
+    /// Tracks the node under the pointer, see [`crate::Node::hovered`].
+    /// Hovered nodes are highlighted and their labels are shown.
+    ///
+    /// Hit-testing is done every frame the pointer is over the widget,
+    /// so keep it disabled if hover is not used.
+    ///
+    /// Default: `false`
+    pub fn with_node_hover_enabled(mut self, enabled: bool) -> Self {
+        self.node_hover_enabled = enabled;
+        self
+    }
+
+    /// Tracks the edge under the pointer, see [`crate::Edge::hovered`].
+    /// Hovered edges are highlighted, so it is clear which edge a click hits.
+    ///
+    /// Nodes take precedence: no edge is hovered while the pointer is over a node.
+    ///
+    /// Default: `false`
+    pub fn with_edge_hover_enabled(mut self, enabled: bool) -> Self {
+        self.edge_hover_enabled = enabled;
+        self
+    }
+
+    /// Distance in screen points from the outline of an edge within which the pointer still
+    /// hits the edge when selecting, clicking or hovering it. It does not change with the
+    /// zoom, so thin edges stay easy to hit when zoomed out.
+    ///
+    /// Default: `3.0`
+    pub fn with_edge_select_tolerance(mut self, tolerance: f32) -> Self {
+        self.edge_select_tolerance = tolerance;
+        self
+    }
 }
 
 /// Represents graph navigation settings.
 #[derive(Debug, Clone)]
 pub struct SettingsNavigation {
     pub(crate) fit_to_screen_enabled: bool,
+    pub(crate) fit_to_selected: bool,
+    pub(crate) fit_selection_fallback: bool,
+    pub(crate) fit_to_labels: bool,
+    pub(crate) double_click_fit: bool,
     pub(crate) zoom_and_pan_enabled: bool,
     pub(crate) screen_padding: f32,
     pub(crate) zoom_speed: f32,
+    pub(crate) invert_zoom: bool,
+    pub(crate) zoom_min: f32,
+    pub(crate) zoom_max: f32,
+    pub(crate) animation_duration_secs: f32,
+    pub(crate) pan_sensitivity: f32,
+    pub(crate) pan_button: PointerButton,
+    pub(crate) pan_inertia: bool,
+    pub(crate) pan_bounds: Option<f32>,
+    pub(crate) marquee_zoom: bool,
+    pub(crate) fit_key: Option<Key>,
+    pub(crate) keyboard_navigation: bool,
+    pub(crate) keyboard_pan_step: f32,
+    pub(crate) max_fit_zoom: Option<f32>,
+    pub(crate) show_minimap: bool,
+    pub(crate) minimap_corner: Corner,
+    pub(crate) minimap_size: Vec2,
+    pub(crate) continuous_repaint: bool,
 }
 
 impl Default for SettingsNavigation {
@@ -95,7 +286,28 @@ impl Default for SettingsNavigation {
         Self {
             screen_padding: 0.3,
             zoom_speed: 0.1,
+            invert_zoom: false,
+            zoom_min: 0.01,
+            zoom_max: 100.,
+            animation_duration_secs: 0.,
+            pan_sensitivity: 1.,
+            pan_button: PointerButton::Primary,
+            pan_inertia: false,
+            pan_bounds: Option::default(),
+            marquee_zoom: false,
+            fit_key: None,
+            keyboard_navigation: false,
+            keyboard_pan_step: 500.,
+            max_fit_zoom: Option::default(),
+            show_minimap: false,
+            minimap_corner: Corner::BottomRight,
+            minimap_size: Vec2::new(200., 150.),
+            continuous_repaint: false,
             fit_to_screen_enabled: true,
+            fit_to_selected: false,
+            fit_selection_fallback: true,
+            fit_to_labels: false,
+            double_click_fit: false,
             zoom_and_pan_enabled: false,
         }
     }
@@ -119,6 +331,39 @@ impl SettingsNavigation {
         self
     }
 
+    /// Fits only the selected nodes to the screen instead of the whole graph.
+    ///
+    /// Useful to frame a subset of nodes, e.g. the results of a search.
+    /// See [`SettingsNavigation::with_fit_selection_fallback`] for the case of no selection.
+    ///
+    /// Default: `false`
+    pub fn with_fit_to_selected(mut self, enabled: bool) -> Self {
+        self.fit_to_selected = enabled;
+        self
+    }
+
+    /// When fitting the selected nodes and nothing is selected, fits the whole graph.
+    /// Otherwise the view is left unchanged.
+    ///
+    /// Default: `true`
+    pub fn with_fit_selection_fallback(mut self, enabled: bool) -> Self {
+        self.fit_selection_fallback = enabled;
+        self
+    }
+
+    /// Includes node labels into the framed area when fitting to the screen, so that labels
+    /// of nodes at the border of the graph are not cut off.
+    ///
+    /// Labels are measured with the fonts of the widget on every fit. Nodes without a label
+    /// are not measured, so graphs without labels are framed as before. Arrowheads end at the
+    /// node outlines and are always inside of the framed area.
+    ///
+    /// Default: `false`
+    pub fn with_fit_to_labels(mut self, enabled: bool) -> Self {
+        self.fit_to_labels = enabled;
+        self
+    }
+
     /// Zoom with ctrl + mouse wheel, pan with mouse drag.
     ///
     /// Default: `false`
@@ -133,17 +378,273 @@ impl SettingsNavigation {
         self
     }
 
-    /// Controls the speed of the zoom.
+    /// Upper limit of the zoom applied when fitting the graph to the screen.
+    ///
+    /// Without a limit a graph of a single node or a tiny cluster is magnified to fill
+    /// the whole widget. With a limit such graphs are centered at a reasonable scale instead.
+    /// Zooming manually is not affected.
+    ///
+    /// Default: `None`
+    pub fn with_max_fit_zoom(mut self, max_zoom: Option<f32>) -> Self {
+        self.max_fit_zoom = max_zoom;
+        self
+    }
+
+    /// Keeps a part of the graph on the screen when panning and zooming, so that it cannot be
+    /// lost off-screen.
+    ///
+    /// The margin is the fraction of the widget width and height the graph has to overlap,
+    /// clamped to `0.0..=1.0`. A graph smaller than that has to stay entirely in the widget.
+    /// `0.0` only keeps the graph touching the widget edge. Fitting to the screen and
+    /// animations are not bounded.
+    ///
+    /// Default: `None`
+    pub fn with_pan_bounds(mut self, margin: Option<f32>) -> Self {
+        self.pan_bounds = margin.map(|m| m.clamp(0., 1.));
+        self
+    }
+
+    /// Controls the speed of the zoom with the mouse wheel.
+    ///
+    /// Every frame with wheel input changes the zoom by this fraction. Pinch gestures are not
+    /// affected, they zoom by the factor of the gesture so that the graph follows the fingers.
     pub fn with_zoom_speed(mut self, speed: f32) -> Self {
         self.zoom_speed = speed;
         self
     }
+
+    /// Inverts the zoom direction of both the mouse wheel and pinch gestures,
+    /// so that scrolling up zooms out.
+    ///
+    /// Default: `false`
+    pub fn with_invert_zoom(mut self, invert: bool) -> Self {
+        self.invert_zoom = invert;
+        self
+    }
+
+    /// Lower limit of the zoom. Must not be greater than the upper limit.
+    ///
+    /// Default: `0.01`
+    pub fn with_zoom_min(mut self, min: f32) -> Self {
+        self.zoom_min = min;
+        self
+    }
+
+    /// Upper limit of the zoom. Must not be less than the lower limit.
+    ///
+    /// Default: `100.0`
+    pub fn with_zoom_max(mut self, max: f32) -> Self {
+        self.zoom_max = max;
+        self
+    }
+
+    /// Duration of animated transitions of the view, e.g. when fitting the graph
+    /// to the screen or with [`crate::GraphView::animate_to`]. `0.0` disables animations.
+    ///
+    /// The view approaches its target by a fraction of the remaining distance every frame,
+    /// so the duration is the time scale of the transition rather than its exact length.
+    ///
+    /// Default: `0.0`
+    pub fn with_animation_duration_secs(mut self, duration: f32) -> Self {
+        self.animation_duration_secs = duration;
+        self
+    }
+
+    /// Multiplier applied to the pointer movement when panning.
+    ///
+    /// Values above `1` pan faster than the cursor moves, values below `1` pan slower
+    /// allowing for more precise navigation.
+    ///
+    /// Default: `1.0`
+    pub fn with_pan_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.pan_sensitivity = sensitivity;
+        self
+    }
+
+    /// Pointer button which pans the view when dragged on empty space.
+    ///
+    /// Set it to [`PointerButton::Middle`] to keep the primary drag free, e.g. for
+    /// box selection. The middle button pans with any setting as it has no other use.
+    ///
+    /// Default: [`PointerButton::Primary`]
+    pub fn with_pan_button(mut self, button: PointerButton) -> Self {
+        self.pan_button = button;
+        self
+    }
+
+    /// Keeps the view gliding after a fast pan drag is released, slowing down until it stops.
+    ///
+    /// Pressing the pointer on the widget stops the gliding immediately.
+    ///
+    /// Default: `false`
+    pub fn with_pan_inertia(mut self, enabled: bool) -> Self {
+        self.pan_inertia = enabled;
+        self
+    }
+
+    /// Zooms to a rectangle dragged over empty space while holding ctrl (cmd on macOS).
+    ///
+    /// On release the view frames exactly the dragged rectangle, animated if
+    /// [`SettingsNavigation::with_animation_duration_secs`] is set. The zoom is clamped to the
+    /// zoom limits. Zooming to a rectangle is also available with
+    /// [`crate::GraphView::zoom_to_rect`].
+    ///
+    /// Default: `false`
+    pub fn with_marquee_zoom(mut self, enabled: bool) -> Self {
+        self.marquee_zoom = enabled;
+        self
+    }
+
+    /// Key which frames the selected nodes, or the whole graph if nothing is selected.
+    ///
+    /// The key is handled only when the widget has focus. The widget gets focus
+    /// when it is clicked or dragged. Disabled with `None`.
+    ///
+    /// Default: `None`
+    pub fn with_fit_key(mut self, key: Option<Key>) -> Self {
+        self.fit_key = key;
+        self
+    }
+
+    /// Frames the whole graph when empty space of the widget is double clicked, without
+    /// keeping the camera fitted like [`SettingsNavigation::with_fit_to_screen_enabled`].
+    ///
+    /// Double clicks on nodes and edges are handled as usual.
+    ///
+    /// Default: `false`
+    pub fn with_double_click_fit(mut self, enabled: bool) -> Self {
+        self.double_click_fit = enabled;
+        self
+    }
+
+    /// Pans the view with the arrow keys while the widget has focus.
+    ///
+    /// The widget gets focus when it is clicked or dragged. While it has focus the arrow keys
+    /// are not used by `egui` to move the focus to other widgets.
+    ///
+    /// Default: `false`
+    pub fn with_keyboard_navigation(mut self, enabled: bool) -> Self {
+        self.keyboard_navigation = enabled;
+        self
+    }
+
+    /// Distance in screen points the view is panned by per second while an arrow key is held.
+    ///
+    /// Default: `500.0`
+    pub fn with_keyboard_pan_step(mut self, step: f32) -> Self {
+        self.keyboard_pan_step = step;
+        self
+    }
+
+    /// Shows a minimap with all nodes and the visible part of the graph in a corner of the
+    /// widget. Clicking or dragging in the minimap centers the view on the pointer.
+    ///
+    /// Default: `false`
+    pub fn with_show_minimap(mut self, enabled: bool) -> Self {
+        self.show_minimap = enabled;
+        self
+    }
+
+    /// Corner of the widget where the minimap is placed.
+    ///
+    /// Default: [`Corner::BottomRight`]
+    pub fn with_minimap_corner(mut self, corner: Corner) -> Self {
+        self.minimap_corner = corner;
+        self
+    }
+
+    /// Size of the minimap in screen points.
+    ///
+    /// Default: `200.0` x `150.0`
+    pub fn with_minimap_size(mut self, size: Vec2) -> Self {
+        self.minimap_size = size;
+        self
+    }
+
+    /// Repaints the widget on every frame.
+    ///
+    /// By default a repaint is only requested while the view or the nodes are moving: while
+    /// dragging, during animations, gliding and smoothing, and while the layout moves nodes.
+    /// An idle graph lets `egui` sleep until the next input. Enable it if the graph is
+    /// changed from outside of the UI, e.g. by live data, or request repaints yourself.
+    ///
+    /// Default: `false`
+    pub fn with_continuous_repaint(mut self, enabled: bool) -> Self {
+        self.continuous_repaint = enabled;
+        self
+    }
 }
 
 /// `SettingsStyle` stores settings for the style of the graph.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct SettingsStyle {
     pub(crate) labels_always: bool,
+    pub(crate) labels_on_hover: bool,
+    pub(crate) label_color: Option<Color32>,
+    pub(crate) legend: Option<Legend>,
+    pub(crate) edge_direction_ticks: bool,
+    pub(crate) edge_arrows: bool,
+    pub(crate) edge_arrow_size: Option<f32>,
+    pub(crate) self_loop_size: Option<f32>,
+    pub(crate) tick_spacing: f32,
+    pub(crate) label_background: Option<LabelBackground>,
+    pub(crate) selection_box: SelectionBox,
+    pub(crate) edge_label_color: Option<Color32>,
+    pub(crate) edge_label_min_size: Option<f32>,
+    pub(crate) show_selection_hud: bool,
+    pub(crate) draw_order: DrawOrder,
+    pub(crate) enable_glow: bool,
+    pub(crate) avoid_label_overlap: bool,
+    pub(crate) curve_segments: CurveSegments,
+    pub(crate) draw_selection_hull: bool,
+    pub(crate) position_lerp: Option<f32>,
+    pub(crate) cull_offscreen: bool,
+    pub(crate) hover_highlight_neighbors: bool,
+    pub(crate) fade_opacity: f32,
+    pub(crate) grid: Option<f32>,
+    pub(crate) grid_color: Option<Color32>,
+    pub(crate) lod_threshold: Option<f32>,
+    pub(crate) color_selected: Option<Color32>,
+    pub(crate) color_highlighted: Option<Color32>,
+    pub(crate) color_node: Option<Color32>,
+    pub(crate) color_edge: Option<Color32>,
+}
+
+impl Default for SettingsStyle {
+    fn default() -> Self {
+        Self {
+            labels_always: false,
+            labels_on_hover: true,
+            label_color: Option::default(),
+            legend: Option::default(),
+            edge_direction_ticks: false,
+            edge_arrows: true,
+            edge_arrow_size: Option::default(),
+            self_loop_size: Option::default(),
+            tick_spacing: 100.,
+            label_background: Option::default(),
+            selection_box: SelectionBox::default(),
+            edge_label_color: Option::default(),
+            edge_label_min_size: Option::default(),
+            show_selection_hud: false,
+            draw_order: DrawOrder::default(),
+            enable_glow: false,
+            avoid_label_overlap: false,
+            curve_segments: CurveSegments::default(),
+            draw_selection_hull: false,
+            position_lerp: Option::default(),
+            cull_offscreen: true,
+            hover_highlight_neighbors: false,
+            fade_opacity: 0.2,
+            grid: Option::default(),
+            grid_color: Option::default(),
+            lod_threshold: Option::default(),
+            color_selected: Option::default(),
+            color_highlighted: Option::default(),
+            color_node: Option::default(),
+            color_edge: Option::default(),
+        }
+    }
 }
 
 impl SettingsStyle {
@@ -156,6 +657,37 @@ impl SettingsStyle {
         Self::default()
     }
 
+    /// Creates [`SettingsStyle`] with colors matching the dark egui theme, see
+    /// [`SettingsStyle::from_visuals`].
+    pub fn dark() -> Self {
+        Self::from_visuals(&Visuals::dark())
+    }
+
+    /// Creates [`SettingsStyle`] with colors matching the light egui theme, see
+    /// [`SettingsStyle::from_visuals`].
+    pub fn light() -> Self {
+        Self::from_visuals(&Visuals::light())
+    }
+
+    /// Creates [`SettingsStyle`] with colors taken from the given egui visuals, e.g.
+    /// `ui.visuals()`, and default values otherwise.
+    ///
+    /// Sets the node, edge, selected and highlighted colors and the grid color. By default
+    /// these colors follow the style of the egui context the graph is drawn in, so the presets
+    /// are useful to draw the graph in a theme other than the one of the surrounding ui.
+    /// Selected edges use the selection color of nodes instead of the active widget color.
+    pub fn from_visuals(visuals: &Visuals) -> Self {
+        let widgets = &visuals.widgets;
+        Self::default()
+            .with_color_node(Some(widgets.inactive.fg_stroke.color))
+            .with_color_edge(Some(widgets.inactive.fg_stroke.color))
+            .with_color_selected(Some(visuals.selection.stroke.color))
+            .with_color_highlighted(Some(widgets.hovered.fg_stroke.color))
+            .with_grid_color(Some(
+                widgets.noninteractive.bg_stroke.color.gamma_multiply(0.5),
+            ))
+    }
+
     /// Whether to show labels always or when interacted.
     ///
     /// Default is false.
@@ -163,4 +695,391 @@ impl SettingsStyle {
         self.labels_always = always;
         self
     }
+
+    /// Whether to show labels of hovered nodes, see
+    /// [`SettingsInteraction::with_node_hover_enabled`].
+    ///
+    /// Default is true.
+    pub fn with_labels_on_hover(mut self, on_hover: bool) -> Self {
+        self.labels_on_hover = on_hover;
+        self
+    }
+
+    /// Color of node labels. Without it labels use the color of the node ring or fill.
+    ///
+    /// Default is `None`.
+    pub fn with_label_color(mut self, color: Option<Color32>) -> Self {
+        self.label_color = color;
+        self
+    }
+
+    /// Color of selected nodes and edges and of the selection hull. Without it nodes and the
+    /// hull use the selection color and edges use the active widget color of the egui style.
+    ///
+    /// Default is `None`.
+    pub fn with_color_selected(mut self, color: Option<Color32>) -> Self {
+        self.color_selected = color;
+        self
+    }
+
+    /// Color of edges highlighted as incident to selected nodes, see
+    /// [`SettingsInteraction::with_highlight_incident_edges`]. Without it the hovered widget
+    /// color of the egui style is used.
+    ///
+    /// Default is `None`.
+    pub fn with_color_highlighted(mut self, color: Option<Color32>) -> Self {
+        self.color_highlighted = color;
+        self
+    }
+
+    /// Fill color of nodes without their own color, see [`crate::Node::set_color`]. Without
+    /// it the inactive widget color of the egui style is used.
+    ///
+    /// Default is `None`.
+    pub fn with_color_node(mut self, color: Option<Color32>) -> Self {
+        self.color_node = color;
+        self
+    }
+
+    /// Color of edges without their own color, see [`crate::Edge::set_color`]. Without it the
+    /// inactive widget color of the egui style is used.
+    ///
+    /// Default is `None`.
+    pub fn with_color_edge(mut self, color: Option<Color32>) -> Self {
+        self.color_edge = color;
+        self
+    }
+
+    /// Legend drawn in the corner of the widget explaining colors and shapes of the graph.
+    ///
+    /// Visibility can be toggled with [`Legend::with_visible`].
+    ///
+    /// Default: `None`
+    pub fn with_legend(mut self, legend: Legend) -> Self {
+        self.legend = Some(legend);
+        self
+    }
+
+    /// Draws arrow tips at the ends of edges of directed graphs.
+    /// Disable to draw a directed graph like an undirected one.
+    ///
+    /// Default: `true`
+    pub fn with_edge_arrows(mut self, enabled: bool) -> Self {
+        self.edge_arrows = enabled;
+        self
+    }
+
+    /// Size of arrow tips in canvas units, overriding the size of the edge display.
+    ///
+    /// Default: `None`
+    pub fn with_edge_arrow_size(mut self, size: Option<f32>) -> Self {
+        self.edge_arrow_size = size;
+        self
+    }
+
+    /// Size of self-loops relative to the radius of their node, overriding the loop size of
+    /// the edge display. Parallel loops of the same node grow by one radius each.
+    ///
+    /// Default: `None`
+    pub fn with_self_loop_size(mut self, size: Option<f32>) -> Self {
+        self.self_loop_size = size;
+        self
+    }
+
+    /// Draws small chevrons along directed edges pointing in the direction of the edge.
+    ///
+    /// Makes the direction of long edges readable even when their ends are off-screen.
+    ///
+    /// Default: `false`
+    pub fn with_edge_direction_ticks(mut self, enabled: bool) -> Self {
+        self.edge_direction_ticks = enabled;
+        self
+    }
+
+    /// Distance in screen pixels between direction ticks along an edge.
+    ///
+    /// Default: `100.0`
+    pub fn with_tick_spacing(mut self, spacing: f32) -> Self {
+        self.tick_spacing = spacing;
+        self
+    }
+
+    /// Draws a background behind node and edge labels to keep them legible on busy graphs.
+    ///
+    /// Default: `None`
+    pub fn with_label_background(mut self, background: LabelBackground) -> Self {
+        self.label_background = Some(background);
+        self
+    }
+
+    /// Look of the box dragged with shift held to select nodes, see
+    /// [`SettingsInteraction::with_node_selection_multi_enabled`].
+    ///
+    /// Default: [`SelectionBox::default`]
+    pub fn with_selection_box(mut self, selection_box: SelectionBox) -> Self {
+        self.selection_box = selection_box;
+        self
+    }
+
+    /// Color of edge labels. Without it labels use the color of the edge.
+    ///
+    /// Edge labels are set with [`crate::Edge::with_label`] and shown for selected edges or
+    /// with [`SettingsStyle::with_labels_always`].
+    ///
+    /// Default: `None`
+    pub fn with_edge_label_color(mut self, color: Option<Color32>) -> Self {
+        self.edge_label_color = color;
+        self
+    }
+
+    /// Hides edge labels whose font is smaller than the given size in screen pixels,
+    /// as edge labels scale with the zoom.
+    ///
+    /// Default: `None`
+    pub fn with_edge_label_min_size(mut self, size: Option<f32>) -> Self {
+        self.edge_label_min_size = size;
+        self
+    }
+
+    /// Shows a panel in the bottom left corner of the widget with the label, index, degree
+    /// and position of the selected node. If multiple nodes are selected the first one is shown.
+    ///
+    /// Default: `false`
+    pub fn with_selection_hud(mut self, show: bool) -> Self {
+        self.show_selection_hud = show;
+        self
+    }
+
+    /// Order in which nodes are drawn. Nodes drawn later appear on top of the others
+    /// and are preferred when clicking or dragging overlapping nodes.
+    ///
+    /// Default: [`DrawOrder::SelectedLast`]
+    pub fn with_draw_order(mut self, order: DrawOrder) -> Self {
+        self.draw_order = order;
+        self
+    }
+
+    /// Draws a soft halo behind nodes which have a glow intensity set with
+    /// [`crate::Node::set_glow`].
+    ///
+    /// Default: `false`
+    pub fn with_glow(mut self, enabled: bool) -> Self {
+        self.enable_glow = enabled;
+        self
+    }
+
+    /// Hides node labels which overlap labels of more important nodes on the screen.
+    ///
+    /// Labels of selected and dragged nodes are always shown, then nodes with
+    /// more incident edges take precedence.
+    ///
+    /// Default: `false`
+    pub fn with_avoid_label_overlap(mut self, enabled: bool) -> Self {
+        self.avoid_label_overlap = enabled;
+        self
+    }
+
+    /// Number of straight segments used to draw curved edges and loops.
+    ///
+    /// More segments make curves smoother but cost more to tessellate. On graphs with many
+    /// curved edges prefer [`CurveSegments::Adaptive`], which spends segments only on curves
+    /// that are long on the screen.
+    ///
+    /// Default: [`CurveSegments::Auto`]
+    pub fn with_curve_segments(mut self, segments: CurveSegments) -> Self {
+        self.curve_segments = segments;
+        self
+    }
+
+    /// Draws a translucent rounded hull behind the selected nodes so that a multiselection
+    /// reads as a group. A single selected node gets a circle and two nodes get a capsule.
+    ///
+    /// Default: `false`
+    pub fn with_selection_hull(mut self, enabled: bool) -> Self {
+        self.draw_selection_hull = enabled;
+        self
+    }
+
+    /// Smooths rendering of node movement. Nodes are drawn moving towards their locations
+    /// with the given time constant in seconds instead of jumping there, which hides jitter
+    /// of layouts updating locations every frame.
+    ///
+    /// Only rendering is affected: node locations stay authoritative and are used for
+    /// interactions. Dragged nodes are never smoothed.
+    ///
+    /// Default: `None`
+    pub fn with_position_lerp(mut self, duration: Option<f32>) -> Self {
+        self.position_lerp = duration;
+        self
+    }
+
+    /// Skips drawing nodes and edges which are outside of the widget.
+    ///
+    /// Nodes are culled by their bounds and edges when both endpoints lie beyond the same side
+    /// of the widget, so edges crossing the widget are still drawn. Bounds are padded by
+    /// 50 screen points to keep labels, loops and curved edges sticking into the widget.
+    ///
+    /// Disable it for custom displays drawing far beyond their outline.
+    ///
+    /// Default: `true`
+    pub fn with_culling(mut self, enabled: bool) -> Self {
+        self.cull_offscreen = enabled;
+        self
+    }
+
+    /// Fades everything but the hovered node, its incident edges and its neighbors regardless
+    /// of edge directions. The graph is drawn as usual again as soon as the hover ends.
+    ///
+    /// Requires [`SettingsInteraction::with_node_hover_enabled`].
+    ///
+    /// Default: `false`
+    pub fn with_hover_highlight_neighbors(mut self, enabled: bool) -> Self {
+        self.hover_highlight_neighbors = enabled;
+        self
+    }
+
+    /// Opacity of elements faded by [`SettingsStyle::with_hover_highlight_neighbors`],
+    /// clamped to `0.0..=1.0`.
+    ///
+    /// Default: `0.2`
+    pub fn with_fade_opacity(mut self, opacity: f32) -> Self {
+        self.fade_opacity = opacity.clamp(0., 1.);
+        self
+    }
+
+    /// Draws a background grid with the given spacing in canvas units, which moves and scales
+    /// with the view. When zoomed out the spacing doubles as often as needed to keep lines
+    /// apart. Non-positive spacings are ignored.
+    ///
+    /// Without it the grid of [`SettingsInteraction::with_snap_to_grid`] is drawn, if any.
+    ///
+    /// Default: `None`
+    pub fn with_grid(mut self, spacing: Option<f32>) -> Self {
+        self.grid = spacing.filter(|s| *s > 0.);
+        self
+    }
+
+    /// Color of the grid lines. Without it the grid uses a faint color of the style.
+    ///
+    /// Default: `None`
+    pub fn with_grid_color(mut self, color: Option<Color32>) -> Self {
+        self.grid_color = color;
+        self
+    }
+
+    /// Zoom below which the graph is drawn with less detail: nodes become plain dots and edges
+    /// thin straight lines without tips, labels or curvature, and loops are hidden. Full detail
+    /// returns when zoomed in above the threshold.
+    ///
+    /// Speeds up drawing of big graphs zoomed far out and keeps them legible. Custom displays
+    /// are not drawn below the threshold. Tune it to the graph size, e.g. `0.3`.
+    ///
+    /// Default: `None`
+    pub fn with_lod_threshold(mut self, zoom: Option<f32>) -> Self {
+        self.lod_threshold = zoom;
+        self
+    }
+}
+
+/// Order in which nodes are drawn by the widget.
+///
+/// Nodes drawn later are on top and are hit first by the pointer. Nodes of the same rank are
+/// drawn in the order of their indices. Edges are always drawn beneath nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DrawOrder {
+    /// Nodes are drawn in the order of their indices.
+    Insertion,
+    /// Nodes are drawn in the order of their [`crate::Node::z`], which is `0` by default.
+    ByZ,
+    /// Nodes are drawn in the order of their `z`, but selected and dragged nodes are always
+    /// drawn on top of the others regardless of `z`.
+    #[default]
+    SelectedLast,
+}
+
+/// Number of straight segments used to draw a curved edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CurveSegments {
+    /// Curves are flattened by the egui tessellator with its default tolerance.
+    #[default]
+    Auto,
+    /// Every curve is drawn with the same number of segments.
+    Fixed(usize),
+    /// Number of segments is proportional to the length of the curve on the screen
+    /// and clamped to the `min..=max` range. If `min` is greater than `max`, `max` is used.
+    Adaptive {
+        pixels_per_segment: f32,
+        min: usize,
+        max: usize,
+    },
+}
+
+/// Background drawn behind labels. Its size is derived from the label text bounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelBackground {
+    pub color: Color32,
+    pub padding: f32,
+    pub rounding: f32,
+}
+
+impl Default for LabelBackground {
+    fn default() -> Self {
+        Self {
+            color: Color32::from_black_alpha(160),
+            padding: 2.,
+            rounding: 2.,
+        }
+    }
+}
+
+/// Box drawn while nodes are selected by dragging a box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelectionBox {
+    /// Color of the border and of the fill. Without it the border uses the selection stroke
+    /// color and the fill uses the selection background color of the egui style.
+    pub color: Option<Color32>,
+    pub stroke_width: f32,
+    /// Opacity multiplier of the fill color.
+    pub fill_alpha: f32,
+    pub dashed: bool,
+}
+
+impl Default for SelectionBox {
+    fn default() -> Self {
+        Self {
+            color: None,
+            stroke_width: 1.,
+            fill_alpha: 0.25,
+            dashed: true,
+        }
+    }
+}
+
+/// Corner of the widget used to anchor overlays drawn on top of the graph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    /// Places a rect of the given `size` in the corner of `outer` keeping `margin` from its sides.
+    pub(crate) fn place(self, outer: Rect, size: Vec2, margin: f32) -> Rect {
+        let left = outer.left() + margin;
+        let right = outer.right() - margin - size.x;
+        let top = outer.top() + margin;
+        let bottom = outer.bottom() - margin - size.y;
+
+        let min = match self {
+            Corner::TopLeft => Pos2::new(left, top),
+            Corner::TopRight => Pos2::new(right, top),
+            Corner::BottomLeft => Pos2::new(left, bottom),
+            Corner::BottomRight => Pos2::new(right, bottom),
+        };
+
+        Rect::from_min_size(min, size)
+    }
 }
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use egui::{Pos2, Rect};
+use petgraph::stable_graph::{IndexType, NodeIndex};
+
+/// Uniform grid over bounding rects of nodes in canvas coordinates.
+///
+/// Finds nodes which may contain a position without scanning all nodes. Every node is stored
+/// in all cells its rect overlaps, so a lookup only has to check the nodes of a single cell.
+/// Nodes overlapping more than [`MAX_CELLS_PER_NODE`] cells are kept aside and checked on every
+/// lookup, and nodes with non-finite rects are left out.
+#[derive(Debug, Clone)]
+pub(crate) struct SpatialIndex<Ix: IndexType> {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<NodeIndex<Ix>>>,
+    large: Vec<NodeIndex<Ix>>,
+}
+
+/// Number of cells a node rect may overlap before it is kept aside of the grid.
+const MAX_CELLS_PER_NODE: i64 = 64;
+
+impl<Ix: IndexType> SpatialIndex<Ix> {
+    /// Builds the index with cells sized after the median node extent, so that a few huge
+    /// nodes do not coarsen the grid.
+    pub fn new(nodes: &[(NodeIndex<Ix>, Rect)]) -> Self {
+        let mut extents = nodes
+            .iter()
+            .map(|(_, rect)| rect.width().max(rect.height()))
+            .filter(|extent| extent.is_finite())
+            .collect::<Vec<_>>();
+        extents.sort_by(f32::total_cmp);
+        let cell_size = extents
+            .get(extents.len() / 2)
+            .copied()
+            .unwrap_or(1.)
+            .max(1.);
+
+        let mut index = Self {
+            cell_size,
+            cells: HashMap::new(),
+            large: Vec::new(),
+        };
+        for (idx, rect) in nodes {
+            if !(rect.min.is_finite() && rect.max.is_finite()) {
+                continue;
+            }
+
+            let (min, max) = (index.cell(rect.min), index.cell(rect.max));
+            let cells = (i64::from(max.0) - i64::from(min.0) + 1)
+                * (i64::from(max.1) - i64::from(min.1) + 1);
+            if cells > MAX_CELLS_PER_NODE {
+                index.large.push(*idx);
+                continue;
+            }
+
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    index.cells.entry((x, y)).or_default().push(*idx);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Returns nodes whose rects may contain the `pos`.
+    pub fn candidates(&self, pos: Pos2) -> impl Iterator<Item = NodeIndex<Ix>> + '_ {
+        let cell = self
+            .cells
+            .get(&self.cell(pos))
+            .map_or(&[][..], Vec::as_slice);
+        cell.iter().chain(&self.large).copied()
+    }
+
+    fn cell(&self, pos: Pos2) -> (i32, i32) {
+        (
+            (pos.x / self.cell_size).floor() as i32,
+            (pos.y / self.cell_size).floor() as i32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Vec2;
+
+    use super::*;
+
+    #[test]
+    fn test_candidates_of_large_grid() {
+        // 10k nodes of radius 5 placed on a grid with spacing 20
+        let nodes = (0..10_000)
+            .map(|i| {
+                let center = Pos2::new((i % 100) as f32 * 20., (i / 100) as f32 * 20.);
+                (
+                    NodeIndex::<u32>::new(i),
+                    Rect::from_center_size(center, Vec2::splat(10.)),
+                )
+            })
+            .collect::<Vec<_>>();
+        let index = SpatialIndex::new(&nodes);
+
+        let candidates = index
+            .candidates(Pos2::new(20. * 42. + 3., 20. * 17. - 2.))
+            .collect::<Vec<_>>();
+        assert!(candidates.contains(&NodeIndex::new(17 * 100 + 42)));
+        assert!(candidates.len() <= 4, "{} candidates", candidates.len());
+
+        assert_eq!(index.candidates(Pos2::new(-500., -500.)).count(), 0);
+    }
+
+    #[test]
+    fn test_degenerate_rects() {
+        let small = Rect::from_center_size(Pos2::ZERO, Vec2::splat(10.));
+        let nodes = [
+            (NodeIndex::<u32>::new(0), small),
+            (
+                NodeIndex::new(1),
+                Rect::from_center_size(Pos2::ZERO, Vec2::splat(1e9)),
+            ),
+            (
+                NodeIndex::new(2),
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(f32::NAN, 5.)),
+            ),
+            (NodeIndex::new(3), Rect::EVERYTHING),
+        ];
+        let index = SpatialIndex::new(&nodes);
+
+        // the huge rect is found everywhere without being spread over the cells
+        let candidates = index.candidates(Pos2::new(1., 1.)).collect::<Vec<_>>();
+        assert_eq!(candidates, vec![NodeIndex::new(0), NodeIndex::new(1)]);
+        let candidates = index.candidates(Pos2::new(1e6, -1e6)).collect::<Vec<_>>();
+        assert_eq!(candidates, vec![NodeIndex::new(1)]);
+        assert!(index
+            .cells
+            .values()
+            .all(|idxs| idxs == &[NodeIndex::new(0)]));
+    }
+}